@@ -0,0 +1,29 @@
+//! Checks the YSCL front matter and ```` ```yscl ```` blocks of every
+//! Markdown file in the given directories (or the current directory).
+//!
+//! Prints each error as `file:line:column: message`,
+//! and exits with status 1 if there are any.
+
+use std::process::ExitCode;
+use yscl::markdown::check_docs;
+
+fn main() -> ExitCode {
+    let mut roots: Vec<String> = std::env::args().skip(1).collect();
+    if roots.is_empty() {
+        roots.push(".".to_owned());
+    }
+
+    let mut has_errors = false;
+    for root in roots {
+        for error in check_docs(root) {
+            eprintln!("{}", error);
+            has_errors = true;
+        }
+    }
+
+    if has_errors {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
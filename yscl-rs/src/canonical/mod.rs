@@ -0,0 +1,152 @@
+//! Canonical form and stable content hashing.
+//!
+//! The canonical form of a document sorts every map's keys
+//! (by their UTF-8 bytes) and keeps list order.
+//! The canonical text is a fixed printing of the canonical form,
+//! which is itself a valid YSCL document:
+//!
+//! - Each entry and element is on its own line, indented by 4 spaces per level.
+//! - Empty maps and lists are printed as `{}` and `[]`.
+//! - In atoms, `"`, `\`, and newlines are escaped as `\"`, `\\`, and `\n`;
+//!   other control characters are escaped as `\u` followed by 6 hex digits.
+//!   Everything else is printed as is.
+//!
+//! The content hash is the SHA-256 of the canonical text,
+//! so it does not change between Rust versions, platforms, or processes,
+//! and two documents that differ only in key order have the same hash.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let a = parse_doc(r#"
+//! name = "yscl"
+//! version = "1.0.0"
+//! "#).unwrap();
+//! let b = parse_doc(r#"
+//! version = "1.0.0"
+//! // A comment.
+//! name = "yscl"
+//! "#).unwrap();
+//!
+//! assert_eq!(canonical_string(&a), "name = \"yscl\"\nversion = \"1.0.0\"\n");
+//! assert_eq!(content_hash(&a), content_hash(&b));
+//! ```
+
+use crate::tree::*;
+
+use std::fmt::{self, Write};
+
+pub(crate) mod sha256;
+
+/// A SHA-256 digest of a document's canonical text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ContentHash(pub [u8; 32]);
+
+impl ContentHash {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Formats the hash as 64 lowercase hex digits.
+impl fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a copy of `map` with every map's keys sorted.
+pub fn canonicalize(map: &Map) -> Map {
+    let mut entries: Vec<MapEntry> = map
+        .entries
+        .iter()
+        .map(|entry| MapEntry {
+            key: entry.key.clone(),
+            value: canonicalize_node(&entry.value),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.as_bytes().cmp(b.key.as_bytes()));
+    Map { entries }
+}
+
+fn canonicalize_node(node: &Node) -> Node {
+    match node {
+        Node::Atom(atom) => Node::Atom(atom.clone()),
+        Node::Map(map) => Node::Map(canonicalize(map)),
+        Node::List(list) => Node::List(List {
+            elements: list.elements.iter().map(canonicalize_node).collect(),
+        }),
+    }
+}
+
+/// Returns the canonical text of `map`.
+pub fn canonical_string(map: &Map) -> String {
+    let mut out = String::new();
+    write_entries(&mut out, &canonicalize(map), 0);
+    out
+}
+
+/// Returns the SHA-256 of the canonical text of `map`.
+pub fn content_hash(map: &Map) -> ContentHash {
+    ContentHash(sha256::sha256(canonical_string(map).as_bytes()))
+}
+
+fn write_entries(out: &mut String, map: &Map, depth: usize) {
+    for entry in &map.entries {
+        indent(out, depth);
+        out.push_str(&entry.key);
+        out.push_str(" = ");
+        write_node(out, &entry.value, depth);
+        out.push('\n');
+    }
+}
+
+fn write_node(out: &mut String, node: &Node, depth: usize) {
+    match node {
+        Node::Atom(atom) => write_atom(out, &atom.value),
+        Node::Map(map) if map.entries.is_empty() => out.push_str("{}"),
+        Node::Map(map) => {
+            out.push_str("{\n");
+            write_entries(out, map, depth + 1);
+            indent(out, depth);
+            out.push('}');
+        }
+        Node::List(list) if list.elements.is_empty() => out.push_str("[]"),
+        Node::List(list) => {
+            out.push_str("[\n");
+            for element in &list.elements {
+                indent(out, depth + 1);
+                write_node(out, element, depth + 1);
+                out.push('\n');
+            }
+            indent(out, depth);
+            out.push(']');
+        }
+    }
+}
+
+fn write_atom(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                write!(out, "\\u{:06x}", c as u32).expect("Writing to a String should succeed")
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("    ");
+    }
+}
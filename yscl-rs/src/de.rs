@@ -0,0 +1,509 @@
+//! Deserializing YSCL into Rust types with [serde](https://serde.rs).
+//!
+//! Requires the `serde` feature.
+//!
+//! - Maps deserialize into structs and maps, and lists into sequences and tuples.
+//! - Since atoms are strings, numbers, `bool`s, and `char`s are parsed
+//!   from atom text, using the same rules as [`FromAtom`].
+//! - `Option<T>` fields may be omitted (they become `None`);
+//!   a present value is always `Some`.
+//! - `()` and unit structs are empty atoms (`""`).
+//! - A unit enum variant is an atom containing the variant's name.
+//!   Any other externally tagged variant is a map with exactly one entry,
+//!   whose key is the variant's name.
+//!   Internally and adjacently tagged enums are maps with a tag entry.
+//!
+//! Internally tagged and untagged enums (and other self-describing targets,
+//! like `serde_json::Value`) see each atom through `deserialize_any`.
+//! An atom is presented as a `bool` or number if its text is exactly how
+//! [`ser`](crate::ser) writes that value (e.g., `"true"`, `"-3"`, or `"0.5"`,
+//! but not `"+3"` or `"1.0"`), and as a string otherwise.
+//! So in those enums, numeric and `bool` fields must be written in that form,
+//! and a `String` field cannot hold text like `"80"` or `"true"`.
+//!
+//! Errors report the [`Path`] of the offending node.
+//!
+//! ## Example
+//! ```rust
+//! use serde::Deserialize;
+//! use yscl::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! #[serde(rename_all = "snake_case")]
+//! enum Level {
+//!     Debug,
+//!     Info,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Server {
+//!     port: u16,
+//!     #[serde(default)]
+//!     verbose: bool,
+//!     level: Level,
+//!     hosts: Vec<String>,
+//!     timeout_secs: Option<u64>,
+//! }
+//!
+//! let server: Server = from_str(r#"
+//! port = "8080"
+//! level = "info"
+//! hosts = [
+//!     "127.0.0.1"
+//! ]
+//! "#).unwrap();
+//!
+//! assert_eq!(
+//!     server,
+//!     Server {
+//!         port: 8080,
+//!         verbose: false,
+//!         level: Level::Info,
+//!         hosts: vec!["127.0.0.1".to_owned()],
+//!         timeout_secs: None,
+//!     },
+//! );
+//!
+//! let err = from_str::<Server>(r#"
+//! port = "80"
+//! level = "info"
+//! hosts = [
+//!     "127.0.0.1"
+//! ]
+//! timeout_secs = "soon"
+//! "#).unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     r#"timeout_secs: expected u64, found "soon" (invalid digit found in string)"#,
+//! );
+//! ```
+
+use crate::{parse::*, path::*, tree::*, typed::*};
+
+use serde::de::{self, value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, Visitor};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeserializeError {
+    /// The source is not a valid YSCL document.
+    Syntax(ParseError),
+    /// The node at `path` does not match the target type.
+    Invalid { path: Path, message: String },
+}
+
+impl DeserializeError {
+    /// Prepends `segment` to the error's path.
+    fn under(self, segment: PathSegment) -> Self {
+        match self {
+            DeserializeError::Invalid { mut path, message } => {
+                path.segments.insert(0, segment);
+                DeserializeError::Invalid { path, message }
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializeError::Syntax(ParseError::UnexpectedChar(c, i)) => {
+                write!(f, "unexpected {:?} at byte {}", c, i)
+            }
+            DeserializeError::Syntax(ParseError::UnexpectedEoi) => {
+                write!(f, "unexpected end of input")
+            }
+            DeserializeError::Syntax(ParseError::DuplicateKey(key, i)) => {
+                write!(f, "duplicate key {:?} at byte {}", key, i)
+            }
+            DeserializeError::Invalid { path, message } if path.is_root() => {
+                write!(f, "{}", message)
+            }
+            DeserializeError::Invalid { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeserializeError::Invalid {
+            path: Path::root(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Parses `src` and deserializes it as a `T`.
+pub fn from_str<T: DeserializeOwned>(src: &str) -> Result<T, DeserializeError> {
+    let map = parse_doc(src).map_err(DeserializeError::Syntax)?;
+    from_map(&map)
+}
+
+/// Deserializes an already parsed document as a `T`.
+pub fn from_map<'de, T: de::Deserialize<'de>>(map: &'de Map) -> Result<T, DeserializeError> {
+    T::deserialize(NodeDeserializer::new(map))
+}
+
+/// Deserializes a node as a `T`.
+/// Error paths are relative to `node`.
+pub fn from_node<'de, T: de::Deserialize<'de>>(node: &'de Node) -> Result<T, DeserializeError> {
+    T::deserialize(NodeDeserializer::new(node))
+}
+
+/// A serde [`Deserializer`](de::Deserializer) for a borrowed node.
+#[derive(Clone, Copy, Debug)]
+pub struct NodeDeserializer<'de> {
+    node: NodeRef<'de>,
+}
+
+impl<'de> NodeDeserializer<'de> {
+    pub fn new(node: impl Into<NodeRef<'de>>) -> Self {
+        Self { node: node.into() }
+    }
+
+    fn wrong_kind(self, expected: &str) -> DeserializeError {
+        de::Error::custom(format!("expected {}, found {}", expected, self.node.kind()))
+    }
+
+    fn atom(self) -> Result<&'de str, DeserializeError> {
+        match self.node {
+            NodeRef::Atom(atom) => Ok(&atom.value),
+            _ => Err(self.wrong_kind("atom")),
+        }
+    }
+
+    fn parse<T: FromAtom>(self) -> Result<T, DeserializeError> {
+        let value = self.atom()?;
+        T::from_atom(value).map_err(|reason| {
+            de::Error::custom(format!(
+                "expected {}, found {:?} ({})",
+                T::EXPECTED,
+                value,
+                reason
+            ))
+        })
+    }
+}
+
+macro_rules! deserialize_via_from_atom {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for NodeDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.node {
+            NodeRef::Atom(atom) => visit_atom(&atom.value, visitor),
+            NodeRef::Map(map) => visitor.visit_map(MapDeserializer::new(map)),
+            NodeRef::List(list) => visitor.visit_seq(ListDeserializer::new(list)),
+        }
+    }
+
+    deserialize_via_from_atom! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.atom()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.atom()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.atom()? {
+            "" => visitor.visit_unit(),
+            value => Err(de::Error::custom(format!(
+                "expected an empty atom, found {:?}",
+                value
+            ))),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.node {
+            NodeRef::List(list) => visitor.visit_seq(ListDeserializer::new(list)),
+            _ => Err(self.wrong_kind("list")),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.node {
+            NodeRef::Map(map) => visitor.visit_map(MapDeserializer::new(map)),
+            _ => Err(self.wrong_kind("map")),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.node {
+            NodeRef::Atom(atom) => visitor.visit_enum(BorrowedStrDeserializer::new(&atom.value)),
+            NodeRef::Map(map) if map.entries.len() == 1 => visitor.visit_enum(EnumDeserializer {
+                entry: &map.entries[0],
+            }),
+            _ => Err(self.wrong_kind("atom or map with exactly one entry")),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Visits `value` as a `bool` or number if it is in the form
+/// [`ser`](crate::ser) writes, so that buffered content (e.g., of internally
+/// tagged enums) can later be deserialized as that type.
+/// Otherwise, visits `value` as a string.
+fn visit_atom<'de, V: Visitor<'de>>(
+    value: &'de str,
+    visitor: V,
+) -> Result<V::Value, DeserializeError> {
+    fn canonical<T: std::str::FromStr + ToString>(value: &str) -> Option<T> {
+        value
+            .parse()
+            .ok()
+            .filter(|parsed: &T| parsed.to_string() == value)
+    }
+
+    match value {
+        "true" => return visitor.visit_bool(true),
+        "false" => return visitor.visit_bool(false),
+        _ => {}
+    }
+    if let Some(n) = canonical::<u64>(value) {
+        visitor.visit_u64(n)
+    } else if let Some(n) = canonical::<i64>(value) {
+        visitor.visit_i64(n)
+    } else if let Some(x) = canonical::<f64>(value).filter(|x| x.is_finite()) {
+        visitor.visit_f64(x)
+    } else {
+        visitor.visit_borrowed_str(value)
+    }
+}
+
+struct MapDeserializer<'de> {
+    entries: std::slice::Iter<'de, MapEntry>,
+    current: Option<&'de MapEntry>,
+}
+
+impl<'de> MapDeserializer<'de> {
+    fn new(map: &'de Map) -> Self {
+        Self {
+            entries: map.entries.iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(entry) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.current = Some(entry);
+        seed.deserialize(BorrowedStrDeserializer::new(&entry.key))
+            .map(Some)
+            .map_err(|err: DeserializeError| err.under(PathSegment::Key(entry.key.clone())))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let entry = self
+            .current
+            .take()
+            .expect("next_value_seed should be called after next_key_seed");
+        seed.deserialize(NodeDeserializer::new(&entry.value))
+            .map_err(|err| err.under(PathSegment::Key(entry.key.clone())))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct ListDeserializer<'de> {
+    elements: std::iter::Enumerate<std::slice::Iter<'de, Node>>,
+}
+
+impl<'de> ListDeserializer<'de> {
+    fn new(list: &'de List) -> Self {
+        Self {
+            elements: list.elements.iter().enumerate(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ListDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some((i, element)) = self.elements.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(NodeDeserializer::new(element))
+            .map(Some)
+            .map_err(|err| err.under(PathSegment::Index(i)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+/// An externally tagged enum variant, written as a map with one entry.
+struct EnumDeserializer<'de> {
+    entry: &'de MapEntry,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed
+            .deserialize(BorrowedStrDeserializer::new(&self.entry.key))
+            .map_err(|err: DeserializeError| self.under(err))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> EnumDeserializer<'de> {
+    fn value(&self) -> NodeDeserializer<'de> {
+        NodeDeserializer::new(&self.entry.value)
+    }
+
+    fn under(&self, err: DeserializeError) -> DeserializeError {
+        err.under(PathSegment::Key(self.entry.key.clone()))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for EnumDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(self.value()).map_err(|err| self.under(err))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self.value())
+            .map_err(|err| self.under(err))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(self.value(), visitor).map_err(|err| self.under(err))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(self.value(), visitor).map_err(|err| self.under(err))
+    }
+}
@@ -0,0 +1,305 @@
+//! Structural diffs between YSCL trees.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let old = parse_doc(r#"
+//! version = "1.0.0"
+//! licenses = [
+//!     "MIT"
+//!     "APACHE"
+//! ]
+//! "#).unwrap();
+//! let new = parse_doc(r#"
+//! version = "1.1.0"
+//! licenses = [
+//!     "MIT"
+//! ]
+//! homepage = "https://example.com"
+//! "#).unwrap();
+//!
+//! let changes = diff(&old, &new);
+//! assert_eq!(
+//!     render_diff(&changes),
+//!     r#"~ version: "1.0.0" -> "1.1.0"
+//! - licenses[1]: "APACHE"
+//! + homepage: "https://example.com"
+//! "#,
+//! );
+//! ```
+
+use crate::{escape::*, path::*, tree::*};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Change {
+    pub path: Path,
+    pub kind: ChangeKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// The node exists in the new tree but not in the old one.
+    Added(Node),
+    /// The node exists in the old tree but not in the new one.
+    Removed(Node),
+    /// The node exists in both trees, but
+    /// either both are atoms with different values,
+    /// or the nodes are of different kinds.
+    Changed { old: Node, new: Node },
+}
+
+/// Returns the changes required to turn `old` into `new`.
+///
+/// Maps are compared key by key.
+/// Lists are compared using a longest common subsequence,
+/// so inserting or removing an element in the middle of a list
+/// does not mark every subsequent element as changed.
+/// When elements are both removed and inserted at the same place,
+/// they are compared pairwise.
+///
+/// Paths of [`ChangeKind::Removed`] changes refer to positions in `old`.
+/// All other paths refer to positions in `new`.
+pub fn diff<'a>(old: impl Into<NodeRef<'a>>, new: impl Into<NodeRef<'a>>) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_node(&mut changes, &Path::root(), old.into(), new.into());
+    changes
+}
+
+/// Renders one line per change.
+pub fn render_diff(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(|change| format!("{}\n", change))
+        .collect()
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Added(node) => write!(f, "+ {}: {}", self.path, Inline(node.as_ref())),
+            ChangeKind::Removed(node) => write!(f, "- {}: {}", self.path, Inline(node.as_ref())),
+            ChangeKind::Changed { old, new } => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                Inline(old.as_ref()),
+                Inline(new.as_ref())
+            ),
+        }
+    }
+}
+
+fn diff_node(changes: &mut Vec<Change>, path: &Path, old: NodeRef, new: NodeRef) {
+    match (old, new) {
+        (NodeRef::Atom(old_atom), NodeRef::Atom(new_atom)) if old_atom == new_atom => {}
+        (NodeRef::Map(old_map), NodeRef::Map(new_map)) => diff_map(changes, path, old_map, new_map),
+        (NodeRef::List(old_list), NodeRef::List(new_list)) => {
+            diff_list(changes, path, old_list, new_list)
+        }
+        _ => changes.push(Change {
+            path: path.clone(),
+            kind: ChangeKind::Changed {
+                old: old.to_owned(),
+                new: new.to_owned(),
+            },
+        }),
+    }
+}
+
+fn diff_map(changes: &mut Vec<Change>, path: &Path, old: &Map, new: &Map) {
+    for old_entry in &old.entries {
+        let entry_path = path.with_key(old_entry.key.clone());
+        match new.get(&old_entry.key) {
+            Some(new_value) => diff_node(
+                changes,
+                &entry_path,
+                old_entry.value.as_ref(),
+                new_value.as_ref(),
+            ),
+            None => changes.push(Change {
+                path: entry_path,
+                kind: ChangeKind::Removed(old_entry.value.clone()),
+            }),
+        }
+    }
+
+    for new_entry in &new.entries {
+        if !old.contains_key(&new_entry.key) {
+            changes.push(Change {
+                path: path.with_key(new_entry.key.clone()),
+                kind: ChangeKind::Added(new_entry.value.clone()),
+            });
+        }
+    }
+}
+
+fn diff_list(changes: &mut Vec<Change>, path: &Path, old: &List, new: &List) {
+    let old = &old.elements;
+    let new = &new.elements;
+
+    let mut old_start = 0;
+    let mut new_start = 0;
+    let matches = longest_common_subsequence(old, new)
+        .into_iter()
+        .chain(std::iter::once((old.len(), new.len())));
+    for (old_end, new_end) in matches {
+        let paired = (old_end - old_start).min(new_end - new_start);
+        for offset in 0..paired {
+            diff_node(
+                changes,
+                &path.with_index(new_start + offset),
+                old[old_start + offset].as_ref(),
+                new[new_start + offset].as_ref(),
+            );
+        }
+        for (i, removed) in old
+            .iter()
+            .enumerate()
+            .take(old_end)
+            .skip(old_start + paired)
+        {
+            changes.push(Change {
+                path: path.with_index(i),
+                kind: ChangeKind::Removed(removed.clone()),
+            });
+        }
+        for (j, added) in new
+            .iter()
+            .enumerate()
+            .take(new_end)
+            .skip(new_start + paired)
+        {
+            changes.push(Change {
+                path: path.with_index(j),
+                kind: ChangeKind::Added(added.clone()),
+            });
+        }
+        old_start = old_end + 1;
+        new_start = new_end + 1;
+    }
+}
+
+/// Returns the index pairs of equal elements
+/// in a longest common subsequence, in increasing order.
+///
+/// Uses Hirschberg's algorithm, so it needs linear space,
+/// after trimming the common prefix and suffix.
+pub(crate) fn longest_common_subsequence(old: &[Node], new: &[Node]) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut pairs: Vec<_> = (0..prefix).map(|i| (i, i)).collect();
+    hirschberg(&mut pairs, old_middle, new_middle, prefix, prefix);
+    pairs.extend((0..suffix).map(|k| (old.len() - suffix + k, new.len() - suffix + k)));
+    pairs
+}
+
+/// Pushes the index pairs of an LCS of `old` and `new`,
+/// offset by `old_start` and `new_start`.
+fn hirschberg(
+    pairs: &mut Vec<(usize, usize)>,
+    old: &[Node],
+    new: &[Node],
+    old_start: usize,
+    new_start: usize,
+) {
+    if old.is_empty() || new.is_empty() {
+        return;
+    }
+    if old.len() == 1 {
+        if let Some(j) = new.iter().position(|node| *node == old[0]) {
+            pairs.push((old_start, new_start + j));
+        }
+        return;
+    }
+
+    // Split `new` where the LCS of the upper half of `old` with a prefix of `new`,
+    // plus that of the lower half with the rest, is longest.
+    let mid = old.len() / 2;
+    let upper = lcs_lengths(old[..mid].iter(), new.iter());
+    let lower = lcs_lengths(old[mid..].iter().rev(), new.iter().rev());
+    let split = (0..=new.len())
+        .max_by_key(|&j| (upper[j] + lower[new.len() - j], std::cmp::Reverse(j)))
+        .expect("Range should be non-empty");
+
+    hirschberg(pairs, &old[..mid], &new[..split], old_start, new_start);
+    hirschberg(
+        pairs,
+        &old[mid..],
+        &new[split..],
+        old_start + mid,
+        new_start + split,
+    );
+}
+
+/// Returns the LCS lengths of `old` and each prefix of `new`
+/// (the length for the first `j` elements of `new` is at index `j`).
+fn lcs_lengths<'a>(
+    old: impl Iterator<Item = &'a Node>,
+    new: impl Iterator<Item = &'a Node> + Clone,
+) -> Vec<usize> {
+    let mut lengths = vec![0; new.clone().count() + 1];
+    for old_node in old {
+        // The previous row's length at `j`.
+        let mut diagonal = 0;
+        for (j, new_node) in new.clone().enumerate() {
+            let above = lengths[j + 1];
+            lengths[j + 1] = if old_node == new_node {
+                diagonal + 1
+            } else {
+                above.max(lengths[j])
+            };
+            diagonal = above;
+        }
+    }
+    lengths
+}
+
+/// Formats a node on a single line, for use in reports.
+struct Inline<'a>(NodeRef<'a>);
+
+impl fmt::Display for Inline<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            NodeRef::Atom(atom) => write!(f, "\"{}\"", escape(&atom.value, EscapeMode::default())),
+            NodeRef::Map(map) => {
+                write!(f, "{{")?;
+                for (i, entry) in map.entries.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(
+                        f,
+                        "{}{} = {}",
+                        separator,
+                        &*entry.key,
+                        Inline(entry.value.as_ref())
+                    )?;
+                }
+                if map.entries.is_empty() {
+                    write!(f, "}}")
+                } else {
+                    write!(f, " }}")
+                }
+            }
+            NodeRef::List(list) => {
+                write!(f, "[")?;
+                for (i, element) in list.elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", Inline(element.as_ref()))?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Overriding document values with environment variables.
+//!
+//! A variable named `<PREFIX>__<SEG>__<SEG>...` overrides the node at the
+//! corresponding path, where each segment is either a map key
+//! (matched case-insensitively) or a list index.
+//! For example, with the prefix `APP`,
+//! `APP__SERVER__PORT` overrides `server.port`
+//! and `APP__HOSTS__0` overrides `hosts[0]`.
+//!
+//! There is no escape for the separator, but keys may still contain `__`
+//! (or start or end with `_`): a key matches if the rest of the name
+//! starts with it, followed by `__` or the end of the name.
+//! For example, `APP__MY__KEY` overrides `my__key`.
+//! If several keys of a map match, exact-case matches are tried first,
+//! then shorter keys, and the first that leads to an existing node wins.
+//!
+//! Only existing nodes can be overridden.
+//! Variables with the prefix that do not match any node are reported
+//! in [`EnvOverlayReport::unmatched`], so typos don't go unnoticed.
+//!
+//! Values that start with `[`, `{`, or `"` are parsed as YSCL
+//! lists, maps, or atoms (using the real grammar, so lists and maps
+//! must span multiple lines).
+//! Any other value is used verbatim as an atom.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//! use std::collections::HashMap;
+//!
+//! let mut map = parse_doc(r#"
+//! server = {
+//!     port = "80"
+//!     hosts = [
+//!         "localhost"
+//!     ]
+//! }
+//! "#).unwrap();
+//!
+//! let vars = HashMap::from([
+//!     ("APP__SERVER__PORT".to_owned(), "9090".to_owned()),
+//!     ("APP__SERVER__HOSTS".to_owned(), "[\n\"a\"\n\"b\"\n]".to_owned()),
+//!     ("APP__SERVER__PROT".to_owned(), "typo".to_owned()),
+//!     ("OTHER".to_owned(), "ignored".to_owned()),
+//! ]);
+//! let report = apply_env(&mut map, "APP", vars).unwrap();
+//!
+//! assert_eq!(
+//!     Node::Map(map),
+//!     yscl_node!({ server = { port = "9090", hosts = ["a", "b"] } }),
+//! );
+//! assert_eq!(report.unmatched, vec!["APP__SERVER__PROT".to_owned()]);
+//! ```
+
+use crate::{parse::*, path::*, tree::*};
+
+use std::fmt;
+
+/// Separates the prefix and path segments in variable names.
+pub const ENV_SEPARATOR: &str = "__";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnvOverlayReport {
+    /// The variables that were applied, and the paths they overrode.
+    pub applied: Vec<(String, Path)>,
+    /// The variables that had the prefix but matched no node.
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnvOverlayError {
+    /// The variable name and the error from parsing its value.
+    /// Byte positions are relative to the value.
+    InvalidValue(String, ParseError),
+}
+
+/// Applies every variable in `vars` that starts with `prefix`
+/// followed by [`ENV_SEPARATOR`].
+///
+/// Variables are applied in lexicographic order of their names,
+/// so the result does not depend on the iteration order of `vars`.
+/// If any value fails to parse, `map` is left unchanged.
+pub fn apply_env<I>(
+    map: &mut Map,
+    prefix: &str,
+    vars: I,
+) -> Result<EnvOverlayReport, EnvOverlayError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let full_prefix = format!("{}{}", prefix, ENV_SEPARATOR);
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(&full_prefix))
+        .collect();
+    vars.sort();
+
+    let mut root = Node::Map(map.clone());
+    let mut report = EnvOverlayReport::default();
+    for (name, value) in vars {
+        let Some(path) = find_path(root.as_ref(), &Path::root(), &name[full_prefix.len()..]) else {
+            report.unmatched.push(name);
+            continue;
+        };
+        let value =
+            parse_value(&value).map_err(|err| EnvOverlayError::InvalidValue(name.clone(), err))?;
+        *path
+            .resolve_mut(&mut root)
+            .expect("Path was found, so it should resolve") = value;
+        report.applied.push((name, path));
+    }
+
+    *map = root.map().expect("Root should remain a map");
+    Ok(report)
+}
+
+/// Like [`apply_env`], using the current process's environment.
+/// Variables whose names or values are not valid Unicode are ignored.
+pub fn apply_process_env(map: &mut Map, prefix: &str) -> Result<EnvOverlayReport, EnvOverlayError> {
+    let vars = std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+    apply_env(map, prefix, vars)
+}
+
+/// Returns the path named by `rest` (the rest of a variable name),
+/// relative to `node`, which is located at `path`.
+fn find_path(node: NodeRef, path: &Path, rest: &str) -> Option<Path> {
+    match node {
+        NodeRef::Map(map) => {
+            let mut candidates: Vec<(u8, &MapEntry)> = map
+                .entries
+                .iter()
+                .filter_map(|entry| {
+                    let head = rest.get(..entry.key.len())?;
+                    let tail = &rest[entry.key.len()..];
+                    if !tail.is_empty() && !tail.starts_with(ENV_SEPARATOR) {
+                        return None;
+                    }
+                    if head == &*entry.key {
+                        Some((0, entry))
+                    } else if head.eq_ignore_ascii_case(&entry.key) {
+                        Some((1, entry))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            candidates.sort_by_key(|(rank, entry)| (*rank, entry.key.len()));
+            candidates.into_iter().find_map(|(_, entry)| {
+                let path = path.with_key(entry.key.clone());
+                match &rest[entry.key.len()..] {
+                    "" => Some(path),
+                    tail => find_path(entry.value.as_ref(), &path, &tail[ENV_SEPARATOR.len()..]),
+                }
+            })
+        }
+        NodeRef::List(list) => {
+            let (segment, tail) = match rest.split_once(ENV_SEPARATOR) {
+                Some((segment, tail)) => (segment, Some(tail)),
+                None => (rest, None),
+            };
+            let index: usize = segment.parse().ok()?;
+            let element = list.elements.get(index)?;
+            let path = path.with_index(index);
+            match tail {
+                None => Some(path),
+                Some(tail) => find_path(element.as_ref(), &path, tail),
+            }
+        }
+        NodeRef::Atom(_) => None,
+    }
+}
+
+fn parse_value(value: &str) -> Result<Node, ParseError> {
+    const KEY: &str = "v = ";
+
+    if !value.starts_with(['[', '{', '"']) {
+        return Ok(Node::Atom(Atom {
+            value: value.to_owned(),
+        }));
+    }
+
+    let mut map = parse_doc(&format!("{}{}", KEY, value)).map_err(|err| match err {
+        ParseError::UnexpectedChar(c, i) => ParseError::UnexpectedChar(c, i - KEY.len()),
+        ParseError::DuplicateKey(key, i) => ParseError::DuplicateKey(key, i - KEY.len()),
+        ParseError::UnexpectedEoi => ParseError::UnexpectedEoi,
+    })?;
+    Ok(map.entries.remove(0).value)
+}
+
+impl fmt::Display for EnvOverlayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvOverlayError::InvalidValue(name, ParseError::UnexpectedChar(c, i)) => {
+                write!(f, "{}: unexpected {:?} at byte {}", name, c, i)
+            }
+            EnvOverlayError::InvalidValue(name, ParseError::UnexpectedEoi) => {
+                write!(f, "{}: unexpected end of input", name)
+            }
+            EnvOverlayError::InvalidValue(name, ParseError::DuplicateKey(key, i)) => {
+                write!(f, "{}: duplicate key {:?} at byte {}", name, key, i)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvOverlayError {}
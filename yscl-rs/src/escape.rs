@@ -0,0 +1,122 @@
+//! Escaping and unescaping the text of atoms.
+//!
+//! YSCL has 4 escape sequences: `\"`, `\\`, `\n`,
+//! and `\u` followed by exactly 6 hex digits
+//! (a Unicode scalar value, e.g., `\u00263A` for ☺).
+//!
+//! [`escape`] and [`unescape`] work on the text *between* the quotes
+//! of an atom, so to write an atom literal, wrap the output of [`escape`]
+//! in `"`.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let value = "say \"hi\" ☺\n";
+//! assert_eq!(escape(value, EscapeMode::Minimal), "say \\\"hi\\\" ☺\\n");
+//! assert_eq!(escape(value, EscapeMode::AsciiOnly), "say \\\"hi\\\" \\u00263a\\n");
+//!
+//! let src = format!("greeting = \"{}\"", escape(value, EscapeMode::AsciiOnly));
+//! assert!(src.is_ascii());
+//! assert_eq!(parse_doc(&src).unwrap().get_as::<String>("greeting").unwrap(), value);
+//! assert_eq!(unescape(&escape(value, EscapeMode::AsciiOnly)), Ok(value.to_owned()));
+//! ```
+
+use crate::parse::*;
+
+/// Which characters [`escape`] writes as escape sequences.
+///
+/// Every mode escapes `"`, `\`, and newlines,
+/// so the output is always valid atom text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EscapeMode {
+    /// Only escape `"`, `\`, and newlines.
+    Minimal,
+    /// Also escape non-printable characters (control characters,
+    /// U+2028 LINE SEPARATOR, U+2029 PARAGRAPH SEPARATOR, and U+FEFF BOM)
+    /// as `\u` followed by 6 hex digits.
+    #[default]
+    Printable,
+    /// Like [`EscapeMode::Printable`], but also escape every non-ASCII
+    /// character, so the output is pure ASCII.
+    AsciiOnly,
+}
+
+/// Escapes `value` so that it can be written between the quotes of an atom.
+pub fn escape(value: &str, mode: EscapeMode) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if needs_unicode_escape(c, mode) => {
+                out.push_str(&format!("\\u{:06x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn needs_unicode_escape(c: char, mode: EscapeMode) -> bool {
+    let is_non_printable = c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}');
+    match mode {
+        EscapeMode::Minimal => false,
+        EscapeMode::Printable => is_non_printable,
+        EscapeMode::AsciiOnly => is_non_printable || !c.is_ascii(),
+    }
+}
+
+/// Decodes the escape sequences in `text`, the text between the quotes
+/// of an atom.
+///
+/// Error indices are byte positions in `text`.
+/// Unescaped `"` and newlines are errors, since they cannot appear in an atom.
+pub fn unescape(text: &str) -> Result<String, ParseError> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' | '\n' => return Err(ParseError::UnexpectedChar(c, i)),
+            '\\' => out.push(unescape_sequence(&mut chars)?),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Decodes an escape sequence, given the chars after its backslash.
+pub(crate) fn unescape_sequence<I>(chars: &mut I) -> Result<char, ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let Some((i_of_c_after_backslash, c_after_backslash)) = chars.next() else {
+        return Err(ParseError::UnexpectedEoi);
+    };
+    match c_after_backslash {
+        '\\' | '"' => Ok(c_after_backslash),
+        'n' => Ok('\n'),
+        'u' => {
+            let mut hex = String::with_capacity(6);
+            let mut last = None;
+            for _ in 0..6 {
+                let Some((hex_i, hex_c)) = chars.next() else {
+                    return Err(ParseError::UnexpectedEoi);
+                };
+                if !hex_c.is_ascii_hexdigit() {
+                    return Err(ParseError::UnexpectedChar(hex_c, hex_i));
+                }
+                hex.push(hex_c);
+                last = Some((hex_i, hex_c));
+            }
+            let (last_hex_i, last_hex_c) = last.expect("There should be 6 hex digits");
+            let codepoint = u32::from_str_radix(&hex, 16).expect("Hex code should always be valid");
+            std::char::from_u32(codepoint).ok_or(ParseError::UnexpectedChar(last_hex_c, last_hex_i))
+        }
+        _ => Err(ParseError::UnexpectedChar(
+            c_after_backslash,
+            i_of_c_after_backslash,
+        )),
+    }
+}
@@ -0,0 +1,322 @@
+//! Building Rust types from trees, without serde.
+//!
+//! [`FromYscl`] builds a value from a node, reporting the full [`Path`]
+//! of anything that goes wrong as an [`AccessError`].
+//! It is implemented for every [`FromAtom`] type, `Option<T>`, `Vec<T>`,
+//! string-keyed `HashMap`s and `BTreeMap`s, and the tree types themselves.
+//!
+//! With the `derive` feature, `#[derive(FromYscl)]` implements it for
+//! structs and enums:
+//!
+//! - A struct with named fields is built from a map.
+//!   Each field is read from the entry with the field's name,
+//!   or with the name given by `#[yscl(rename = "key")]`.
+//!   Entries that don't match a field are ignored.
+//! - A missing `Option<T>` field is `None`.
+//!   A missing field marked `#[yscl(default)]` (or `#[yscl(default = "path")]`)
+//!   is `Default::default()` (or `path()`).
+//!   Any other missing field is an [`AccessError::Missing`].
+//! - A field marked `#[yscl(from_str)]` is parsed from its atom with
+//!   [`FromStr`], so it can have any `FromStr` type.
+//!   Errors describe the expected value as "a value parseable from text",
+//!   or as the given description with `#[yscl(from_str = "a percentage")]`.
+//!   Add `secret` (e.g., `#[yscl(from_str, secret)]`) to replace the atom's
+//!   value by [`REDACTED`] in errors, as for [`FromAtom::SECRET`] types;
+//!   the field can then be a [`Secret<T>`] for any `FromStr` type `T`.
+//! - A struct with a single unnamed field is built like that field.
+//! - A unit enum variant is an atom containing the variant's name.
+//!   Any other variant is a map with exactly one entry, whose key is the
+//!   variant's name, and whose value is the variant's field
+//!   (for a newtype variant) or a map of its fields (for a struct variant).
+//!   Variants can be renamed with `#[yscl(rename = "name")]`.
+//!
+//! ## Example
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # fn main() {
+//! use yscl::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, FromYscl)]
+//! enum Level {
+//!     #[yscl(rename = "debug")]
+//!     Debug,
+//!     #[yscl(rename = "info")]
+//!     Info,
+//! }
+//!
+//! #[derive(Debug, PartialEq, FromYscl)]
+//! struct Server {
+//!     port: u16,
+//!     #[yscl(default)]
+//!     verbose: bool,
+//!     #[yscl(rename = "log_level")]
+//!     level: Level,
+//!     timeout_secs: Option<u64>,
+//! }
+//!
+//! #[derive(Debug, PartialEq, FromYscl)]
+//! struct Config {
+//!     servers: Vec<Server>,
+//! }
+//!
+//! let map = parse_doc(r#"
+//! servers = [
+//!     {
+//!         port = "80"
+//!         log_level = "info"
+//!     }
+//! ]
+//! "#).unwrap();
+//! assert_eq!(
+//!     map.extract::<Config>(),
+//!     Ok(Config {
+//!         servers: vec![Server {
+//!             port: 80,
+//!             verbose: false,
+//!             level: Level::Info,
+//!             timeout_secs: None,
+//!         }],
+//!     }),
+//! );
+//!
+//! let map = parse_doc(r#"
+//! servers = [
+//!     {
+//!         port = "eighty"
+//!         log_level = "info"
+//!     }
+//! ]
+//! "#).unwrap();
+//! assert_eq!(
+//!     map.extract::<Config>().unwrap_err().to_string(),
+//!     r#"servers[0].port: expected u16, found "eighty" (invalid digit found in string)"#,
+//! );
+//! # }
+//! # #[cfg(not(feature = "derive"))]
+//! # fn main() {}
+//! ```
+
+use crate::{path::*, secret::*, tree::*, typed::*};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::BuildHasher,
+    str::FromStr,
+};
+
+#[cfg(feature = "derive")]
+pub use yscl_derive::FromYscl;
+
+/// A type that can be built from a node.
+pub trait FromYscl: Sized {
+    /// Builds a value from `node`, which is located at `path`.
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError>;
+
+    /// Called instead of [`FromYscl::from_yscl`] when the value's key
+    /// is missing from its map.
+    fn from_missing(path: &Path) -> Result<Self, AccessError> {
+        missing(path)
+    }
+}
+
+impl<T: FromAtom> FromYscl for T {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        parse_node(node, path)
+    }
+}
+
+impl<T: FromYscl> FromYscl for Option<T> {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        T::from_yscl(node, path).map(Some)
+    }
+
+    fn from_missing(_path: &Path) -> Result<Self, AccessError> {
+        Ok(None)
+    }
+}
+
+impl<T: FromYscl> FromYscl for Vec<T> {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        expect_list(node, path)?
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| T::from_yscl(element.as_ref(), &path.with_index(i)))
+            .collect()
+    }
+}
+
+impl<T: FromYscl> FromYscl for BTreeMap<String, T> {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        map_entries(node, path)
+    }
+}
+
+impl<T: FromYscl, S: BuildHasher + Default> FromYscl for HashMap<String, T, S> {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        map_entries(node, path)
+    }
+}
+
+fn map_entries<T, C>(node: NodeRef, path: &Path) -> Result<C, AccessError>
+where
+    T: FromYscl,
+    C: FromIterator<(String, T)>,
+{
+    expect_map(node, path)?
+        .entries
+        .iter()
+        .map(|entry| {
+            let value = T::from_yscl(entry.value.as_ref(), &path.with_key(entry.key.clone()))?;
+            Ok((entry.key.to_string(), value))
+        })
+        .collect()
+}
+
+impl FromYscl for Node {
+    fn from_yscl(node: NodeRef, _path: &Path) -> Result<Self, AccessError> {
+        Ok(node.to_owned())
+    }
+}
+
+impl FromYscl for Map {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        expect_map(node, path).cloned()
+    }
+}
+
+impl FromYscl for List {
+    fn from_yscl(node: NodeRef, path: &Path) -> Result<Self, AccessError> {
+        expect_list(node, path).cloned()
+    }
+}
+
+impl Node {
+    /// Builds a `T` from `self`.
+    pub fn extract<T: FromYscl>(&self) -> Result<T, AccessError> {
+        T::from_yscl(self.as_ref(), &Path::root())
+    }
+}
+
+impl Map {
+    /// Builds a `T` from `self`.
+    pub fn extract<T: FromYscl>(&self) -> Result<T, AccessError> {
+        T::from_yscl(NodeRef::Map(self), &Path::root())
+    }
+}
+
+/// Returns `node` as a map, or an [`AccessError::WrongKind`].
+pub fn expect_map<'a>(node: NodeRef<'a>, path: &Path) -> Result<&'a Map, AccessError> {
+    node.map().ok_or_else(|| AccessError::WrongKind {
+        path: path.clone(),
+        expected: NodeKind::Map,
+        actual: node.kind(),
+    })
+}
+
+/// Returns `node` as a list, or an [`AccessError::WrongKind`].
+pub fn expect_list<'a>(node: NodeRef<'a>, path: &Path) -> Result<&'a List, AccessError> {
+    node.list().ok_or_else(|| AccessError::WrongKind {
+        path: path.clone(),
+        expected: NodeKind::List,
+        actual: node.kind(),
+    })
+}
+
+/// Builds the value of the entry `key` of `map` (which is located at `path`)
+/// with `parse`, or with `missing` if there is no such entry.
+///
+/// Used by `#[derive(FromYscl)]`.
+pub fn field<T>(
+    map: &Map,
+    path: &Path,
+    key: &str,
+    parse: impl FnOnce(NodeRef, &Path) -> Result<T, AccessError>,
+    missing: impl FnOnce(&Path) -> Result<T, AccessError>,
+) -> Result<T, AccessError> {
+    let path = path.with_key(Identifier::new(key.to_owned()).expect("Key should be valid"));
+    match map.get(key) {
+        Some(node) => parse(node.as_ref(), &path),
+        None => missing(&path),
+    }
+}
+
+/// Returns an [`AccessError::Missing`].
+pub fn missing<T>(path: &Path) -> Result<T, AccessError> {
+    Err(AccessError::Missing(path.clone()))
+}
+
+/// Parses the atom `node` with [`FromStr`].
+///
+/// `expected` describes the value in errors.
+/// If `secret` is `true`, the atom's value is replaced by [`REDACTED`] in errors.
+pub fn parse_from_str<T>(
+    node: NodeRef,
+    path: &Path,
+    expected: &'static str,
+    secret: bool,
+) -> Result<T, AccessError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let NodeRef::Atom(atom) = node else {
+        return Err(AccessError::WrongKind {
+            path: path.clone(),
+            expected: NodeKind::Atom,
+            actual: node.kind(),
+        });
+    };
+    atom.value
+        .parse()
+        .map_err(|err: T::Err| AccessError::InvalidValue {
+            path: path.clone(),
+            value: if secret {
+                REDACTED.to_owned()
+            } else {
+                atom.value.clone()
+            },
+            expected,
+            reason: err.to_string(),
+        })
+}
+
+/// Returns the only entry of the map `node`,
+/// which holds an enum variant other than a unit variant.
+///
+/// `expected` describes the enum's variants.
+pub fn variant_entry<'a>(
+    node: NodeRef<'a>,
+    path: &Path,
+    expected: &'static str,
+) -> Result<&'a MapEntry, AccessError> {
+    let map = expect_map(node, path)?;
+    match &map.entries[..] {
+        [entry] => Ok(entry),
+        entries => Err(AccessError::InvalidValue {
+            path: path.clone(),
+            value: entries
+                .iter()
+                .map(|entry| &*entry.key)
+                .collect::<Vec<_>>()
+                .join(", "),
+            expected,
+            reason: format!("expected exactly one entry, found {}", entries.len()),
+        }),
+    }
+}
+
+/// Returns the error for a variant name that doesn't match any variant.
+pub fn unknown_variant<T>(
+    name: &str,
+    path: &Path,
+    expected: &'static str,
+) -> Result<T, AccessError> {
+    Err(AccessError::InvalidValue {
+        path: path.clone(),
+        value: name.to_owned(),
+        expected,
+        reason: "unknown variant".to_owned(),
+    })
+}
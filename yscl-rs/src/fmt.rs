@@ -0,0 +1,338 @@
+//! A comment-preserving formatter.
+//!
+//! The formatter normalizes layout without changing content:
+//!
+//! - Each level of nesting is indented by one [`FmtOptions::indent`].
+//! - Entries are written as `key = value`, with one space around the `=`.
+//! - Runs of blank lines are shortened to at most
+//!   [`FmtOptions::max_blank_lines`], and blank lines at the start or end
+//!   of the document, a map, or a list are removed.
+//! - Maps and lists that contain nothing (not even comments)
+//!   are written as `{}` and `[]`.
+//! - Trailing whitespace is removed, and the document ends with
+//!   exactly one newline (unless it is empty).
+//!
+//! Comments, entry order, and atoms are kept exactly as written
+//! (atoms are not even re-escaped),
+//! so formatting never changes the parsed [`Map`](crate::tree::Map).
+//! Formatting is idempotent.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let src = r#"
+//!
+//! // The package name.
+//! name="yscl"
+//!
+//!
+//!
+//!   deps   =   {
+//!         // Pinned.
+//!    json = "1.0.0"
+//!
+//! }
+//! tags = [
+//! ]
+//! "#;
+//!
+//! let formatted = format_doc(src).unwrap();
+//! assert_eq!(
+//!     formatted,
+//!     r#"// The package name.
+//! name = "yscl"
+//!
+//! deps = {
+//!     // Pinned.
+//!     json = "1.0.0"
+//! }
+//! tags = []
+//! "#,
+//! );
+//! assert_eq!(format_doc(&formatted).unwrap(), formatted);
+//! assert_eq!(parse_doc(&formatted), parse_doc(src));
+//! ```
+
+use crate::parse::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FmtOptions {
+    /// The text written once per level of nesting.
+    /// This should only contain spaces and tabs,
+    /// or the output will not be valid YSCL.
+    pub indent: String,
+    /// The maximum number of consecutive blank lines to keep.
+    pub max_blank_lines: usize,
+}
+
+impl Default for FmtOptions {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_owned(),
+            max_blank_lines: 1,
+        }
+    }
+}
+
+impl FmtOptions {
+    pub fn with_indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    pub fn with_max_blank_lines(mut self, max_blank_lines: usize) -> Self {
+        self.max_blank_lines = max_blank_lines;
+        self
+    }
+}
+
+/// Formats `src` using the default options.
+pub fn format_doc(src: &str) -> Result<String, ParseError> {
+    format_doc_with(src, &FmtOptions::default())
+}
+
+/// Formats `src`.
+/// Returns an error if `src` is not a valid YSCL document.
+pub fn format_doc_with(src: &str, options: &FmtOptions) -> Result<String, ParseError> {
+    let expected = parse_doc(src)?;
+
+    let mut cursor = Cursor {
+        src,
+        pos: 0,
+        line_is_blank: true,
+    };
+    let items = cursor.map_body(false)?;
+
+    let mut out = String::new();
+    write_items(&mut out, &items, options, 0);
+
+    debug_assert_eq!(
+        Ok(expected),
+        parse_doc(&out),
+        "Formatting should never change the parsed document"
+    );
+    Ok(out)
+}
+
+/// Returns `true` if formatting `src` would not change it.
+pub fn is_formatted(src: &str) -> Result<bool, ParseError> {
+    Ok(format_doc(src)? == src)
+}
+
+/// Returns `true` if formatting `src` with `options` would not change it.
+pub fn is_formatted_with(src: &str, options: &FmtOptions) -> Result<bool, ParseError> {
+    Ok(format_doc_with(src, options)? == src)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Item<'a> {
+    Blank,
+    /// The text after the `//`, without trailing whitespace.
+    Comment(&'a str),
+    Entry(&'a str, Value<'a>),
+    Element(Value<'a>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Value<'a> {
+    /// The source text between the quotes, with escapes left as is.
+    Atom(&'a str),
+    Map(Vec<Item<'a>>),
+    List(Vec<Item<'a>>),
+}
+
+/// Parses a document that is already known to be valid,
+/// keeping comments and blank lines.
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+    /// Whether the current line has no tokens yet.
+    line_is_blank: bool,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(c) => ParseError::UnexpectedChar(c, self.pos),
+            None => ParseError::UnexpectedEoi,
+        }
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace() && c != '\n') {
+            self.bump();
+        }
+    }
+
+    /// Parses the items of a map, up to and including its `}`
+    /// (or up to the end of the input, for the top-level map).
+    fn map_body(&mut self, has_closer: bool) -> Result<Vec<Item<'a>>, ParseError> {
+        let mut items = vec![];
+        loop {
+            self.skip_inline_whitespace();
+            match self.peek() {
+                None if !has_closer => return Ok(items),
+                Some('}') if has_closer => {
+                    self.bump();
+                    self.line_is_blank = false;
+                    return Ok(items);
+                }
+                Some(c) if c.is_alphanumeric() || c == '_' => {
+                    self.line_is_blank = false;
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+                        self.bump();
+                    }
+                    let key = &self.src[start..self.pos];
+                    self.skip_inline_whitespace();
+                    if self.peek() != Some('=') {
+                        return Err(self.unexpected());
+                    }
+                    self.bump();
+                    self.skip_inline_whitespace();
+                    items.push(Item::Entry(key, self.value()?));
+                }
+                _ => items.extend(self.trivia()?),
+            }
+        }
+    }
+
+    /// Parses the items of a list, up to and including its `]`.
+    fn list_body(&mut self) -> Result<Vec<Item<'a>>, ParseError> {
+        let mut items = vec![];
+        loop {
+            self.skip_inline_whitespace();
+            match self.peek() {
+                Some(']') => {
+                    self.bump();
+                    self.line_is_blank = false;
+                    return Ok(items);
+                }
+                Some('"' | '{' | '[') => items.push(Item::Element(self.value()?)),
+                _ => items.extend(self.trivia()?),
+            }
+        }
+    }
+
+    /// Parses a newline or a comment.
+    /// A newline only produces an item if it ends a blank line.
+    fn trivia(&mut self) -> Result<Option<Item<'a>>, ParseError> {
+        match self.peek() {
+            Some('\n') => {
+                self.bump();
+                let was_blank = std::mem::replace(&mut self.line_is_blank, true);
+                Ok(Some(Item::Blank).filter(|_| was_blank))
+            }
+            Some('/') => {
+                self.line_is_blank = false;
+                let start = self.pos + "//".len();
+                let end = self.src[self.pos..]
+                    .find('\n')
+                    .map(|i| self.pos + i)
+                    .unwrap_or(self.src.len());
+                self.pos = end;
+                Ok(Some(Item::Comment(self.src[start..end].trim_end())))
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value<'a>, ParseError> {
+        self.line_is_blank = false;
+        match self.bump() {
+            Some('"') => {
+                let start = self.pos;
+                loop {
+                    match self.bump() {
+                        Some('"') => return Ok(Value::Atom(&self.src[start..self.pos - 1])),
+                        Some('\\') => {
+                            self.bump();
+                        }
+                        Some(_) => {}
+                        None => return Err(ParseError::UnexpectedEoi),
+                    }
+                }
+            }
+            Some('{') => Ok(Value::Map(self.map_body(true)?)),
+            Some('[') => Ok(Value::List(self.list_body()?)),
+            Some(c) => Err(ParseError::UnexpectedChar(c, self.pos - c.len_utf8())),
+            None => Err(ParseError::UnexpectedEoi),
+        }
+    }
+}
+
+fn write_items(out: &mut String, items: &[Item], options: &FmtOptions, depth: usize) {
+    let mut pending_blank_lines = 0;
+    let mut is_first = true;
+    for item in items {
+        if let Item::Blank = item {
+            pending_blank_lines += 1;
+            continue;
+        }
+
+        // Blank lines before the first item are dropped,
+        // and so are blank lines after the last item
+        // (since nothing follows them).
+        if !is_first {
+            for _ in 0..pending_blank_lines.min(options.max_blank_lines) {
+                out.push('\n');
+            }
+        }
+        pending_blank_lines = 0;
+        is_first = false;
+
+        write_indent(out, options, depth);
+        match item {
+            Item::Blank => unreachable!("Blank lines should have been skipped"),
+            Item::Comment(text) => {
+                out.push_str("//");
+                out.push_str(text);
+            }
+            Item::Entry(key, value) => {
+                out.push_str(key);
+                out.push_str(" = ");
+                write_value(out, value, options, depth);
+            }
+            Item::Element(value) => write_value(out, value, options, depth),
+        }
+        out.push('\n');
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, options: &FmtOptions, depth: usize) {
+    let (items, opener, closer) = match value {
+        Value::Atom(raw) => {
+            out.push('"');
+            out.push_str(raw);
+            out.push('"');
+            return;
+        }
+        Value::Map(items) => (items, '{', '}'),
+        Value::List(items) => (items, '[', ']'),
+    };
+
+    out.push(opener);
+    if items.iter().any(|item| *item != Item::Blank) {
+        out.push('\n');
+        write_items(out, items, options, depth + 1);
+        write_indent(out, options, depth);
+    }
+    out.push(closer);
+}
+
+fn write_indent(out: &mut String, options: &FmtOptions, depth: usize) {
+    for _ in 0..depth {
+        out.push_str(&options.indent);
+    }
+}
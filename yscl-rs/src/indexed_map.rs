@@ -0,0 +1,162 @@
+use crate::tree::*;
+
+use std::collections::HashMap;
+
+/// A [`Map`] with a hash index over its keys.
+///
+/// [`Map::get`] is a linear search, which is fine for
+/// typical configuration files but slow for very large maps
+/// that are queried in hot paths.
+/// `IndexedMap` makes lookups constant time while still
+/// preserving document order for iteration and conversion
+/// back to a [`Map`].
+///
+/// The cost is extra memory for the index,
+/// and [`IndexedMap::remove`] is linear time
+/// (since the indices of all following entries must be shifted).
+///
+/// ## Example
+/// ```rust
+/// use yscl::prelude::*;
+///
+/// let map = parse_doc(r#"
+/// foo = "1"
+/// bar = "2"
+/// "#).unwrap();
+///
+/// let mut indexed = IndexedMap::try_from(map).unwrap();
+/// assert_eq!(indexed.get("bar"), Some(&yscl_node!("2")));
+///
+/// indexed.insert(Identifier::new("baz".to_owned()).unwrap(), yscl_node!("3"));
+/// assert_eq!(
+///     Node::Map(indexed.into_map()),
+///     yscl_node!({ foo = "1", bar = "2", baz = "3" }),
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IndexedMap {
+    map: Map,
+    index: HashMap<Identifier, usize>,
+}
+
+impl IndexedMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn as_map(&self) -> &Map {
+        &self.map
+    }
+
+    pub fn into_map(self) -> Map {
+        self.map
+    }
+
+    /// Returns the entries in document order.
+    pub fn entries(&self) -> &[MapEntry] {
+        &self.map.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.entries.is_empty()
+    }
+
+    pub fn get<K>(&self, key: &K) -> Option<&Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = *self.index.get(key.as_ref())?;
+        Some(&self.map.entries[index].value)
+    }
+
+    pub fn get_mut<K>(&mut self, key: &K) -> Option<&mut Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = *self.index.get(key.as_ref())?;
+        Some(&mut self.map.entries[index].value)
+    }
+
+    pub fn contains_key<K>(&self, key: &K) -> bool
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.index.contains_key(key.as_ref())
+    }
+
+    /// Behaves like [`Map::insert`].
+    pub fn insert(&mut self, key: Identifier, value: Node) -> Option<Node> {
+        if let Some(&index) = self.index.get(&key) {
+            return Some(std::mem::replace(&mut self.map.entries[index].value, value));
+        }
+        self.push_unchecked(key, value);
+        None
+    }
+
+    /// Behaves like [`Map::try_insert`].
+    pub fn try_insert(&mut self, key: Identifier, value: Node) -> Result<&mut Node, EditError> {
+        if self.index.contains_key(&key) {
+            return Err(EditError::DuplicateKey(key));
+        }
+        self.push_unchecked(key, value);
+        Ok(&mut self
+            .map
+            .entries
+            .last_mut()
+            .expect("Entries should be non-empty after a push")
+            .value)
+    }
+
+    /// Behaves like [`Map::remove`].
+    pub fn remove<K>(&mut self, key: &K) -> Option<Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let removed_index = self.index.remove(key.as_ref())?;
+        for index in self.index.values_mut() {
+            if *index > removed_index {
+                *index -= 1;
+            }
+        }
+        Some(self.map.entries.remove(removed_index).value)
+    }
+
+    fn push_unchecked(&mut self, key: Identifier, value: Node) {
+        self.index.insert(key.clone(), self.map.entries.len());
+        self.map.entries.push(MapEntry { key, value });
+    }
+}
+
+impl PartialEq for IndexedMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl Eq for IndexedMap {}
+
+/// Returns [`EditError::DuplicateKey`] if the map
+/// contains the same key more than once.
+impl TryFrom<Map> for IndexedMap {
+    type Error = EditError;
+
+    fn try_from(map: Map) -> Result<Self, Self::Error> {
+        let mut index = HashMap::with_capacity(map.entries.len());
+        for (i, entry) in map.entries.iter().enumerate() {
+            if index.insert(entry.key.clone(), i).is_some() {
+                return Err(EditError::DuplicateKey(entry.key.clone()));
+            }
+        }
+        Ok(Self { map, index })
+    }
+}
+
+impl From<IndexedMap> for Map {
+    fn from(indexed: IndexedMap) -> Self {
+        indexed.map
+    }
+}
@@ -0,0 +1,234 @@
+//! An optional pass that resolves `${path}` references inside atoms.
+//!
+//! The parser never interprets `${`; atoms keep their literal values
+//! unless [`interpolate`] is called.
+//!
+//! A reference is `${` followed by a [`Path`] and `}`.
+//! It is replaced by the (interpolated) value of the atom at that path,
+//! relative to the document root.
+//! To write a literal `${`, escape it as `$${`.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let map = parse_doc(r#"
+//! base_url = "https://example.com"
+//! api = {
+//!     users = "${base_url}/users"
+//!     posts = "${api.users}/posts"
+//! }
+//! template = "$${not_a_reference}"
+//! "#).unwrap();
+//!
+//! let map = interpolate(&map).unwrap();
+//! assert_eq!(
+//!     Node::Map(map),
+//!     yscl_node!({
+//!         base_url = "https://example.com",
+//!         api = {
+//!             users = "https://example.com/users",
+//!             posts = "https://example.com/users/posts"
+//!         },
+//!         template = "${not_a_reference}"
+//!     }),
+//! );
+//! ```
+
+use crate::{path::*, tree::*};
+
+use std::{collections::HashMap, fmt};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InterpolationError {
+    /// A `${` has no matching `}`.
+    /// Contains the path of the atom.
+    Unterminated(Path),
+    /// The text between `${` and `}` is not a valid path.
+    /// Contains the path of the atom and the text.
+    InvalidReference(Path, String),
+    /// Nothing exists at the referenced path.
+    /// Contains the path of the atom and the referenced path.
+    Unresolved(Path, Path),
+    /// The referenced node is a map or list.
+    /// Contains the path of the atom and the referenced path.
+    NotAnAtom(Path, Path),
+    /// The atoms at these paths reference each other in a cycle.
+    Cycle(Vec<Path>),
+}
+
+/// Returns a copy of `map` with every reference replaced.
+///
+/// On failure, returns every error found (not just the first),
+/// in document order.
+pub fn interpolate(map: &Map) -> Result<Map, Vec<InterpolationError>> {
+    let mut root = Node::Map(map.clone());
+    interpolate_node_in_place(&mut root)?;
+    Ok(root.map().expect("Root should remain a map"))
+}
+
+/// Like [`interpolate`], but edits `map` in place.
+/// If there are errors, `map` is left unchanged.
+pub fn interpolate_in_place(map: &mut Map) -> Result<(), Vec<InterpolationError>> {
+    *map = interpolate(map)?;
+    Ok(())
+}
+
+fn interpolate_node_in_place(root: &mut Node) -> Result<(), Vec<InterpolationError>> {
+    let atom_paths: Vec<Path> = root
+        .as_ref()
+        .depth_first()
+        .filter(|(_, node)| matches!(node, NodeRef::Atom(_)))
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut resolver = Resolver {
+        root: root.as_ref(),
+        resolved: HashMap::new(),
+        in_progress: vec![],
+        errors: vec![],
+    };
+    for path in &atom_paths {
+        // Errors are recorded in `resolver.errors`.
+        let _ = resolver.resolve(path);
+    }
+
+    if !resolver.errors.is_empty() {
+        return Err(resolver.errors);
+    }
+
+    let mut resolved = resolver.resolved;
+    for path in &atom_paths {
+        let Some(Ok(value)) = resolved.remove(path) else {
+            continue;
+        };
+        if let Some(Node::Atom(atom)) = path.resolve_mut(root) {
+            atom.value = value;
+        }
+    }
+    Ok(())
+}
+
+struct Resolver<'a> {
+    root: NodeRef<'a>,
+    /// `Err(())` means resolution failed and the error was already recorded.
+    resolved: HashMap<Path, Result<String, ()>>,
+    in_progress: Vec<Path>,
+    errors: Vec<InterpolationError>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, path: &Path) -> Result<String, ()> {
+        if let Some(result) = self.resolved.get(path) {
+            return result.clone();
+        }
+
+        if let Some(start) = self.in_progress.iter().position(|p| p == path) {
+            let cycle = self.in_progress[start..].to_vec();
+            for member in &cycle {
+                self.resolved.insert(member.clone(), Err(()));
+            }
+            self.errors.push(InterpolationError::Cycle(cycle));
+            return Err(());
+        }
+
+        let Some(NodeRef::Atom(atom)) = path.resolve(self.root) else {
+            unreachable!("Only atom paths should be resolved");
+        };
+
+        self.in_progress.push(path.clone());
+        let result = self.expand(path, &atom.value);
+        self.in_progress.pop();
+
+        // A cycle may have already marked this path as failed.
+        let result = match self.resolved.get(path) {
+            Some(Err(())) => Err(()),
+            _ => result,
+        };
+        self.resolved.insert(path.clone(), result.clone());
+        result
+    }
+
+    fn expand(&mut self, path: &Path, value: &str) -> Result<String, ()> {
+        let mut out = String::with_capacity(value.len());
+        let mut remaining = value;
+        // Keep going after a failed reference, so every error is recorded.
+        let mut failed = false;
+        loop {
+            let Some(dollar) = remaining.find('$') else {
+                out.push_str(remaining);
+                return if failed { Err(()) } else { Ok(out) };
+            };
+            out.push_str(&remaining[..dollar]);
+            remaining = &remaining[dollar..];
+
+            if let Some(rest) = remaining.strip_prefix("$${") {
+                out.push_str("${");
+                remaining = rest;
+            } else if let Some(rest) = remaining.strip_prefix("${") {
+                let Some(close) = rest.find('}') else {
+                    return self.fail(InterpolationError::Unterminated(path.clone()));
+                };
+                let reference = &rest[..close];
+                remaining = &rest[close + 1..];
+                match self.follow(path, reference) {
+                    Ok(value) => out.push_str(&value),
+                    Err(()) => failed = true,
+                }
+            } else {
+                out.push('$');
+                remaining = &remaining[1..];
+            }
+        }
+    }
+
+    fn follow(&mut self, path: &Path, reference: &str) -> Result<String, ()> {
+        let Ok(target) = reference.parse::<Path>() else {
+            return self.fail(InterpolationError::InvalidReference(
+                path.clone(),
+                reference.to_owned(),
+            ));
+        };
+        match target.resolve(self.root) {
+            None => self.fail(InterpolationError::Unresolved(path.clone(), target)),
+            Some(NodeRef::Atom(_)) => self.resolve(&target),
+            Some(_) => self.fail(InterpolationError::NotAnAtom(path.clone(), target)),
+        }
+    }
+
+    fn fail<T>(&mut self, error: InterpolationError) -> Result<T, ()> {
+        self.errors.push(error);
+        Err(())
+    }
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpolationError::Unterminated(path) => {
+                write!(f, "{}: `${{` has no matching `}}`", path)
+            }
+            InterpolationError::InvalidReference(path, reference) => {
+                write!(f, "{}: invalid reference {:?}", path, reference)
+            }
+            InterpolationError::Unresolved(path, target) => {
+                write!(f, "{}: {} does not exist", path, target)
+            }
+            InterpolationError::NotAnAtom(path, target) => {
+                write!(f, "{}: {} is not an atom", path, target)
+            }
+            InterpolationError::Cycle(paths) => {
+                let Some(first) = paths.first() else {
+                    return write!(f, "reference cycle");
+                };
+                write!(f, "{}: reference cycle: ", first)?;
+                for path in paths {
+                    write!(f, "{} -> ", path)?;
+                }
+                write!(f, "{}", first)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InterpolationError {}
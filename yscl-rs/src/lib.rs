@@ -94,15 +94,64 @@
 //! assert_eq!(expected, map);
 //! ```
 
+// Lets `#[derive(FromYscl)]` refer to `::yscl` inside this crate's own tests.
+extern crate self as yscl;
+
 #[macro_use]
 pub mod macros;
 
+pub mod canonical;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod diff;
+pub mod env;
+pub mod escape;
+pub mod extract;
+pub mod fmt;
+pub mod indexed_map;
+pub mod interpolate;
+pub mod loader;
+pub mod markdown;
+pub mod merge;
 pub mod parse;
+pub mod patch;
+pub mod path;
+pub mod query;
+pub mod secret;
+pub mod semantic;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod shared;
 pub mod tree;
+pub mod typed;
+pub mod visit;
+pub mod write;
+
+#[cfg(feature = "serde")]
+pub use de::from_str;
+pub use diff::diff;
+pub use escape::{escape, unescape};
+pub use merge::merge;
+#[cfg(feature = "serde")]
+pub use ser::to_string;
 
 pub mod prelude {
-    pub use crate::{macros::*, parse::*, tree::*};
+    pub use crate::{
+        canonical::*, diff::*, env::*, escape::*, extract::*, fmt::*, indexed_map::*,
+        interpolate::*, loader::*, markdown::*, merge::*, parse::*, patch::*, path::*, query::*,
+        secret::*, semantic::*, shared::*, tree::*, typed::*, visit::*, write::*,
+    };
+
+    #[cfg(feature = "serde")]
+    pub use crate::de::*;
+    // `ser::to_string` would conflict with `write::to_string`.
+    #[cfg(feature = "serde")]
+    pub use crate::ser::{to_map, to_node, SerializeError};
 
+    // `macros` has no public items yet (`yscl_node!` is exported at the crate root),
+    // but the prelude has always re-exported it.
+    #[allow(unused_imports)]
+    pub use crate::macros::*;
     pub use crate::yscl_node;
 }
 
@@ -0,0 +1,320 @@
+//! Loading documents that are split across multiple files.
+//!
+//! A document may have a top-level `include` key whose value is a list of
+//! file paths, relative to the including file.
+//! The included documents are loaded (recursively) and deep-merged in order,
+//! then the including document's own entries are merged on top.
+//! The `include` key itself does not appear in the result.
+//!
+//! Every file must be inside the loader's root directory,
+//! and a file may not (directly or indirectly) include itself.
+//! Including the same file from two different places is fine.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use yscl::prelude::*;
+//!
+//! // config/app.yscl:
+//! //
+//! // include = [
+//! //     "defaults.yscl"
+//! //     "servers/prod.yscl"
+//! // ]
+//! // name = "app"
+//! let map = Loader::new("config").load("config/app.yscl").unwrap();
+//! ```
+
+use crate::{merge::*, parse::*, path::*, tree::*};
+
+use std::{
+    fmt, fs, io,
+    path::{Path as FsPath, PathBuf},
+};
+
+/// The top-level key that lists the files to include.
+pub const INCLUDE_KEY: &str = "include";
+
+#[derive(Clone, Debug)]
+pub struct Loader {
+    pub root: PathBuf,
+    /// Used to merge included documents with each other
+    /// and with the including document.
+    pub merge_options: MergeOptions,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadError {
+    /// The file in which the error occurred.
+    pub file: PathBuf,
+    pub kind: LoadErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadErrorKind {
+    /// The file could not be read.
+    Io(io::ErrorKind),
+    /// A file listed in `include` could not be included.
+    /// `LoadError::file` is the including file,
+    /// and the line and column are those of the include atom.
+    Include {
+        include: PathBuf,
+        error: IncludeErrorKind,
+        line: usize,
+        column: usize,
+    },
+    /// The file is not a valid YSCL document.
+    /// Lines and columns are 1-based, and columns are counted in chars.
+    Syntax {
+        error: ParseError,
+        line: usize,
+        column: usize,
+    },
+    /// The `include` value (or the element at `path`)
+    /// is not a list of atoms.
+    /// The line and column are those of the offending value.
+    InvalidInclude {
+        path: Path,
+        line: usize,
+        column: usize,
+    },
+    /// The loaded path, which resolves outside the root directory.
+    OutsideRoot(PathBuf),
+    /// The chain of files that include each other,
+    /// starting and ending with the same file.
+    Cycle(Vec<PathBuf>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IncludeErrorKind {
+    /// The included file could not be read.
+    Io(io::ErrorKind),
+    /// The included file resolves outside the root directory.
+    OutsideRoot,
+}
+
+impl Loader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            merge_options: MergeOptions::default(),
+        }
+    }
+
+    pub fn with_merge_options(mut self, merge_options: MergeOptions) -> Self {
+        self.merge_options = merge_options;
+        self
+    }
+
+    /// Loads the file at `path` and everything it includes.
+    pub fn load(&self, path: impl AsRef<FsPath>) -> Result<Map, LoadError> {
+        let path = path.as_ref();
+        let root = self.root.canonicalize().map_err(|err| LoadError {
+            file: self.root.clone(),
+            kind: LoadErrorKind::Io(err.kind()),
+        })?;
+        let file = canonicalize_inside(&root, path).map_err(|error| LoadError {
+            file: path.to_owned(),
+            kind: match error {
+                IncludeErrorKind::Io(kind) => LoadErrorKind::Io(kind),
+                IncludeErrorKind::OutsideRoot => LoadErrorKind::OutsideRoot(path.to_owned()),
+            },
+        })?;
+        self.load_file(&root, file, &mut vec![])
+    }
+
+    fn load_file(
+        &self,
+        root: &FsPath,
+        file: PathBuf,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Map, LoadError> {
+        if let Some(start) = stack.iter().position(|ancestor| *ancestor == file) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(file.clone());
+            return Err(LoadError {
+                file,
+                kind: LoadErrorKind::Cycle(cycle),
+            });
+        }
+
+        let src = fs::read_to_string(&file).map_err(|err| LoadError {
+            file: file.clone(),
+            kind: LoadErrorKind::Io(err.kind()),
+        })?;
+        let mut map = parse_doc(&src).map_err(|error| {
+            let (line, column) = line_and_column(&src, &error);
+            LoadError {
+                file: file.clone(),
+                kind: LoadErrorKind::Syntax {
+                    error,
+                    line,
+                    column,
+                },
+            }
+        })?;
+
+        let Some(includes) = map.remove(INCLUDE_KEY) else {
+            return Ok(map);
+        };
+        let includes = include_paths(&includes).map_err(|path| {
+            let (line, column) = value_line_and_column(&src, &path);
+            LoadError {
+                file: file.clone(),
+                kind: LoadErrorKind::InvalidInclude { path, line, column },
+            }
+        })?;
+
+        let dir = file
+            .parent()
+            .expect("Canonical file path should have a parent");
+        stack.push(file.clone());
+        let mut result = Map::default();
+        for (i, include) in includes.into_iter().enumerate() {
+            let include = dir.join(include);
+            let included = canonicalize_inside(root, &include).map_err(|error| {
+                let (line, column) = value_line_and_column(&src, &include_path().with_index(i));
+                LoadError {
+                    file: file.clone(),
+                    kind: LoadErrorKind::Include {
+                        include,
+                        error,
+                        line,
+                        column,
+                    },
+                }
+            })?;
+            let included = self.load_file(root, included, stack)?;
+            merge_into(&mut result, &included, &self.merge_options);
+        }
+        stack.pop();
+
+        merge_into(&mut result, &map, &self.merge_options);
+        Ok(result)
+    }
+}
+
+fn canonicalize_inside(root: &FsPath, path: &FsPath) -> Result<PathBuf, IncludeErrorKind> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|err| IncludeErrorKind::Io(err.kind()))?;
+    if !canonical.starts_with(root) {
+        return Err(IncludeErrorKind::OutsideRoot);
+    }
+    Ok(canonical)
+}
+
+/// Loads the file at `path`, using its directory as the root.
+pub fn load_file(path: impl AsRef<FsPath>) -> Result<Map, LoadError> {
+    let path = path.as_ref();
+    let root = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+        _ => PathBuf::from("."),
+    };
+    Loader::new(root).load(path)
+}
+
+fn include_path() -> Path {
+    Path::root()
+        .with_key(Identifier::new(INCLUDE_KEY.to_owned()).expect("Include key should be valid"))
+}
+
+/// On failure, returns the path of the offending value.
+fn include_paths(includes: &Node) -> Result<Vec<&str>, Path> {
+    let Node::List(list) = includes else {
+        return Err(include_path());
+    };
+    list.elements
+        .iter()
+        .enumerate()
+        .map(|(i, element)| match element {
+            Node::Atom(atom) => Ok(atom.value.as_str()),
+            _ => Err(include_path().with_index(i)),
+        })
+        .collect()
+}
+
+/// Returns the 1-based line and column (in chars) of the value at `path` in `src`.
+fn value_line_and_column(src: &str, path: &Path) -> (usize, usize) {
+    // `src` has already been parsed, so the value should always be found.
+    position(src, value_position(src, path).unwrap_or(0))
+}
+
+/// Returns the 1-based line and column (in chars) of `error` in `src`.
+pub(crate) fn line_and_column(src: &str, error: &ParseError) -> (usize, usize) {
+    let index = match error {
+        ParseError::UnexpectedChar(_, i) | ParseError::DuplicateKey(_, i) => *i,
+        ParseError::UnexpectedEoi => src.len(),
+    };
+    position(src, index)
+}
+
+/// Returns the 1-based line and column (in chars) of the byte position `index` in `src`.
+fn position(src: &str, index: usize) -> (usize, usize) {
+    let before = &src[..index];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        match &self.kind {
+            LoadErrorKind::Io(kind) => write!(f, ": {}", io::Error::from(*kind)),
+            LoadErrorKind::Syntax {
+                error,
+                line,
+                column,
+            } => {
+                write!(f, ":{}:{}: ", line, column)?;
+                write_parse_error(f, error)
+            }
+            LoadErrorKind::Include {
+                include,
+                error,
+                line,
+                column,
+            } => {
+                write!(
+                    f,
+                    ":{}:{}: cannot include {}: ",
+                    line,
+                    column,
+                    include.display()
+                )?;
+                match error {
+                    IncludeErrorKind::Io(kind) => write!(f, "{}", io::Error::from(*kind)),
+                    IncludeErrorKind::OutsideRoot => write!(f, "outside the root directory"),
+                }
+            }
+            LoadErrorKind::InvalidInclude { path, line, column } => {
+                write!(f, ":{}:{}: {} must be a list of atoms", line, column, path)
+            }
+            LoadErrorKind::OutsideRoot(path) => {
+                write!(f, ": {} is outside the root directory", path.display())
+            }
+            LoadErrorKind::Cycle(files) => {
+                write!(f, ": include cycle: ")?;
+                for (i, file) in files.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "{}", file.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Writes `error` without its position.
+pub(crate) fn write_parse_error(f: &mut fmt::Formatter<'_>, error: &ParseError) -> fmt::Result {
+    match error {
+        ParseError::UnexpectedChar(c, _) => write!(f, "unexpected {:?}", c),
+        ParseError::UnexpectedEoi => write!(f, "unexpected end of input"),
+        ParseError::DuplicateKey(key, _) => write!(f, "duplicate key {:?}", key),
+    }
+}
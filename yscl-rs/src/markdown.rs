@@ -0,0 +1,332 @@
+//! Extracting YSCL from Markdown documents.
+//!
+//! A Markdown document can contain YSCL in two places:
+//!
+//! - Front matter: a block at the very start of the document,
+//!   between two [`FRONT_MATTER_DELIMITER`] lines.
+//!   If there is no closing delimiter line, there is no front matter.
+//! - Fenced code blocks whose info string starts with `yscl`
+//!   (e.g., ```` ```yscl ````).
+//!   Both backtick and tilde fences are recognized, following CommonMark,
+//!   so a ```` ```yscl ```` inside a longer or different fence is not a block.
+//!   Blocks inside block quotes are not recognized.
+//!
+//! [`check_markdown`] and [`check_docs`] skip blocks whose info string
+//! contains the word `ignore` (e.g., ```` ```yscl ignore ````),
+//! which is useful for examples of invalid YSCL.
+//!
+//! Each block is extracted as a [`Snippet`], which remembers where it came from,
+//! so parse errors point at lines and columns of the Markdown document.
+//!
+//! ## Example
+//! ````rust
+//! use yscl::prelude::*;
+//!
+//! let markdown = r#"---
+//! title = "Getting started"
+//! ---
+//!
+//! # Getting started
+//!
+//! ```yscl
+//! name = "fibonacci"
+//! ```
+//!
+//! ```yscl
+//! name = "fibonacci
+//! ```
+//! "#;
+//!
+//! let front_matter = front_matter(markdown).unwrap().parse().unwrap();
+//! assert_eq!(Node::Map(front_matter), yscl_node!({ title = "Getting started" }));
+//!
+//! let blocks = fenced_blocks(markdown);
+//! assert_eq!(Node::Map(blocks[0].parse().unwrap()), yscl_node!({ name = "fibonacci" }));
+//!
+//! let errors = check_markdown(markdown);
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].to_string(), "12:18: unexpected '\\n'");
+//! ````
+
+use crate::{loader::*, parse::*, tree::*};
+
+use std::{fmt, fs, io, path::Path as FsPath};
+
+/// The line that opens and closes front matter.
+pub const FRONT_MATTER_DELIMITER: &str = "---";
+
+/// The file extensions that [`check_docs`] treats as Markdown.
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// A YSCL document embedded in a Markdown document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snippet<'a> {
+    /// The YSCL source, without the surrounding delimiter or fence lines.
+    pub src: &'a str,
+    /// The byte position of `src` in the Markdown document.
+    pub start: usize,
+    /// The 1-based line of the Markdown document on which `src` starts.
+    pub line: usize,
+    /// The info string of the opening fence (e.g., `"yscl ignore"`),
+    /// or `""` for front matter.
+    pub info: &'a str,
+}
+
+/// A parse error in a [`Snippet`],
+/// with positions relative to the Markdown document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnippetError {
+    /// The error's byte positions are relative to the Markdown document.
+    pub error: ParseError,
+    /// Lines and columns are 1-based, and columns are counted in chars.
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Snippet<'_> {
+    pub fn parse(&self) -> Result<Map, SnippetError> {
+        parse_doc(self.src).map_err(|error| {
+            let (line, column) = line_and_column(self.src, &error);
+            let error = match error {
+                ParseError::UnexpectedChar(c, i) => ParseError::UnexpectedChar(c, self.start + i),
+                ParseError::UnexpectedEoi => ParseError::UnexpectedEoi,
+                ParseError::DuplicateKey(key, i) => ParseError::DuplicateKey(key, self.start + i),
+            };
+            SnippetError {
+                error,
+                line: self.line + line - 1,
+                column,
+            }
+        })
+    }
+}
+
+/// Returns the front matter of `markdown`, if it has any.
+pub fn front_matter(markdown: &str) -> Option<Snippet<'_>> {
+    front_matter_and_end(markdown).map(|(snippet, _)| snippet)
+}
+
+/// Returns the front matter and the byte position after its closing delimiter line.
+fn front_matter_and_end(markdown: &str) -> Option<(Snippet<'_>, usize)> {
+    let mut lines = lines(markdown);
+    let (_, first) = lines.next()?;
+    if first.trim_end() != FRONT_MATTER_DELIMITER {
+        return None;
+    }
+    let start = first.len();
+    lines
+        .find(|(_, line)| line.trim_end() == FRONT_MATTER_DELIMITER)
+        .map(|(end, closing)| {
+            let snippet = Snippet {
+                src: &markdown[start..end],
+                start,
+                line: 2,
+                info: "",
+            };
+            (snippet, end + closing.len())
+        })
+}
+
+/// Returns every ```` ```yscl ```` fenced code block in `markdown`,
+/// in document order.
+/// Front matter is skipped.
+pub fn fenced_blocks(markdown: &str) -> Vec<Snippet<'_>> {
+    let body_start = front_matter_and_end(markdown)
+        .map(|(_, end)| end)
+        .unwrap_or(0);
+    let first_line = markdown[..body_start].matches('\n').count() + 1;
+
+    let mut blocks = vec![];
+    let mut open: Option<OpenFence> = None;
+    for (line_number, (line_start, line)) in
+        (first_line..).zip(lines(markdown).skip_while(|(line_start, _)| *line_start < body_start))
+    {
+        match &open {
+            None => {
+                if let Some((fence, info)) = opening_fence(line) {
+                    open = Some(OpenFence {
+                        fence,
+                        info,
+                        start: line_start + line.len(),
+                        line: line_number + 1,
+                    });
+                }
+            }
+            Some(fence) => {
+                if is_closing_fence(line, fence.fence) {
+                    if fence.is_yscl() {
+                        blocks.push(fence.snippet(markdown, line_start));
+                    }
+                    open = None;
+                }
+            }
+        }
+    }
+    // Per CommonMark, an unclosed fence runs to the end of the document.
+    if let Some(fence) = open {
+        if fence.is_yscl() {
+            blocks.push(fence.snippet(markdown, markdown.len()));
+        }
+    }
+    blocks
+}
+
+/// Parses the front matter and every fenced block of `markdown`
+/// (except ignored blocks), returning all the errors.
+pub fn check_markdown(markdown: &str) -> Vec<SnippetError> {
+    front_matter(markdown)
+        .into_iter()
+        .chain(fenced_blocks(markdown))
+        .filter(|snippet| !snippet.info.split_whitespace().any(|word| word == "ignore"))
+        .filter_map(|snippet| snippet.parse().err())
+        .collect()
+}
+
+/// Checks every Markdown file (see [`MARKDOWN_EXTENSIONS`])
+/// in the directory `root` and its subdirectories,
+/// returning all the errors, sorted by file.
+///
+/// Symbolic links are not followed.
+/// Files and directories that cannot be read are reported as
+/// [`LoadErrorKind::Io`] errors.
+pub fn check_docs(root: impl AsRef<FsPath>) -> Vec<LoadError> {
+    let mut errors = vec![];
+    check_dir(root.as_ref(), &mut errors);
+    errors
+}
+
+fn check_dir(dir: &FsPath, errors: &mut Vec<LoadError>) {
+    let io_error = |file: &FsPath, err: io::Error| LoadError {
+        file: file.to_owned(),
+        kind: LoadErrorKind::Io(err.kind()),
+    };
+    let mut entries = match fs::read_dir(dir).and_then(|entries| {
+        entries
+            .map(|entry| {
+                let entry = entry?;
+                Ok((entry.path(), entry.file_type()?))
+            })
+            .collect::<io::Result<Vec<_>>>()
+    }) {
+        Ok(entries) => entries,
+        Err(err) => {
+            errors.push(io_error(dir, err));
+            return;
+        }
+    };
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (path, file_type) in entries {
+        if file_type.is_dir() {
+            check_dir(&path, errors);
+        } else if file_type.is_file() && is_markdown(&path) {
+            match fs::read_to_string(&path) {
+                Ok(markdown) => {
+                    errors.extend(
+                        check_markdown(&markdown)
+                            .into_iter()
+                            .map(|error| LoadError {
+                                file: path.clone(),
+                                kind: LoadErrorKind::Syntax {
+                                    error: error.error,
+                                    line: error.line,
+                                    column: error.column,
+                                },
+                            }),
+                    )
+                }
+                Err(err) => errors.push(io_error(&path, err)),
+            }
+        }
+    }
+}
+
+fn is_markdown(path: &FsPath) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| MARKDOWN_EXTENSIONS.contains(&extension))
+}
+
+/// Returns each line (including its line ending) with its byte position.
+fn lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.split_inclusive('\n').scan(0, |start, line| {
+        let line_start = *start;
+        *start += line.len();
+        Some((line_start, line))
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Fence {
+    c: char,
+    len: usize,
+}
+
+struct OpenFence<'a> {
+    fence: Fence,
+    info: &'a str,
+    /// The byte position of the line after the opening fence.
+    start: usize,
+    line: usize,
+}
+
+impl<'a> OpenFence<'a> {
+    fn is_yscl(&self) -> bool {
+        self.info.split_whitespace().next() == Some("yscl")
+    }
+
+    fn snippet(&self, markdown: &'a str, end: usize) -> Snippet<'a> {
+        Snippet {
+            src: &markdown[self.start..end],
+            start: self.start,
+            line: self.line,
+            info: self.info,
+        }
+    }
+}
+
+/// Returns the fence and the info string.
+fn opening_fence(line: &str) -> Option<(Fence, &str)> {
+    let (fence, rest) = fence(line)?;
+    if fence.c == '`' && rest.contains('`') {
+        return None;
+    }
+    Some((fence, rest.trim()))
+}
+
+fn is_closing_fence(line: &str, opening: Fence) -> bool {
+    match fence(line) {
+        Some((fence, rest)) => {
+            fence.c == opening.c && fence.len >= opening.len && rest.trim().is_empty()
+        }
+        None => false,
+    }
+}
+
+/// Returns the fence at the start of `line` (after at most 3 spaces)
+/// and the rest of the line.
+fn fence(line: &str) -> Option<(Fence, &str)> {
+    let unindented = line.trim_start_matches(' ');
+    if line.len() - unindented.len() > 3 {
+        return None;
+    }
+    let c = unindented
+        .chars()
+        .next()
+        .filter(|c| *c == '`' || *c == '~')?;
+    let rest = unindented.trim_start_matches(c);
+    let len = unindented.len() - rest.len();
+    if len < 3 {
+        return None;
+    }
+    Some((Fence { c, len }, rest))
+}
+
+impl fmt::Display for SnippetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        write_parse_error(f, &self.error)
+    }
+}
+
+impl std::error::Error for SnippetError {}
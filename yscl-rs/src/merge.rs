@@ -0,0 +1,236 @@
+//! Layered deep merging of YSCL documents.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let defaults = parse_doc(r#"
+//! server = {
+//!     host = "0.0.0.0"
+//!     port = "80"
+//!     debug = "false"
+//! }
+//! plugins = [
+//!     "auth"
+//! ]
+//! "#).unwrap();
+//! let site = parse_doc(r#"
+//! server = {
+//!     port = "8080"
+//!     debug = "$delete"
+//! }
+//! plugins = [
+//!     "metrics"
+//! ]
+//! "#).unwrap();
+//!
+//! let options = MergeOptions {
+//!     default_list_strategy: ListStrategy::Append,
+//!     ..MergeOptions::default()
+//! }
+//! .with_delete_marker("$delete");
+//! let merged = merge([&defaults, &site], &options);
+//! assert_eq!(
+//!     Node::Map(merged),
+//!     yscl_node!({
+//!         server = { host = "0.0.0.0", port = "8080" },
+//!         plugins = ["auth", "metrics"]
+//!     }),
+//! );
+//! ```
+
+use crate::{path::*, tree::*};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Used for lists whose path does not appear in `list_strategies`.
+    pub default_list_strategy: ListStrategy,
+    /// Overrides the list strategy for the list at a specific path
+    /// (relative to the document root).
+    pub list_strategies: HashMap<Path, ListStrategy>,
+    /// If an upper layer sets a key to an atom with this value,
+    /// the key is removed from the result.
+    /// Markers that have nothing to delete (e.g., markers in lists,
+    /// or in maps that no lower layer defines) are dropped.
+    /// `None` (the default) disables deletion,
+    /// so no atom value is ever treated specially.
+    pub delete_marker: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ListStrategy {
+    /// The upper list replaces the lower list.
+    Replace,
+    /// The upper list's elements are appended to the lower list.
+    Append,
+    /// Map elements whose `field` atoms are equal are deep-merged.
+    /// All other upper elements are appended.
+    MergeByKey(Identifier),
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            default_list_strategy: ListStrategy::Replace,
+            list_strategies: HashMap::new(),
+            delete_marker: None,
+        }
+    }
+}
+
+impl MergeOptions {
+    pub fn with_list_strategy(mut self, path: Path, strategy: ListStrategy) -> Self {
+        self.list_strategies.insert(path, strategy);
+        self
+    }
+
+    pub fn with_delete_marker(mut self, marker: impl Into<String>) -> Self {
+        self.delete_marker = Some(marker.into());
+        self
+    }
+
+    fn list_strategy(&self, path: &Path) -> &ListStrategy {
+        self.list_strategies
+            .get(path)
+            .unwrap_or(&self.default_list_strategy)
+    }
+
+    fn is_delete_marker(&self, node: &Node) -> bool {
+        match (node, &self.delete_marker) {
+            (Node::Atom(atom), Some(marker)) => atom.value == *marker,
+            _ => false,
+        }
+    }
+}
+
+/// Merges `layers` from lowest to highest priority.
+///
+/// Keys keep the position they have in the lowest layer that defines them;
+/// keys first introduced by an upper layer are appended.
+pub fn merge<'a, I>(layers: I, options: &MergeOptions) -> Map
+where
+    I: IntoIterator<Item = &'a Map>,
+{
+    let mut result = Map::default();
+    for layer in layers {
+        merge_into(&mut result, layer, options);
+    }
+    result
+}
+
+/// Merges `upper` into `base` in place.
+pub fn merge_into(base: &mut Map, upper: &Map, options: &MergeOptions) {
+    merge_map(base, upper, options, &Path::root());
+}
+
+fn merge_map(base: &mut Map, upper: &Map, options: &MergeOptions, path: &Path) {
+    for upper_entry in &upper.entries {
+        if options.is_delete_marker(&upper_entry.value) {
+            base.remove(&upper_entry.key);
+            continue;
+        }
+
+        let entry_path = path.with_key(upper_entry.key.clone());
+        match base.get_mut(&upper_entry.key) {
+            Some(base_value) => merge_node(base_value, &upper_entry.value, options, &entry_path),
+            None => {
+                base.insert(
+                    upper_entry.key.clone(),
+                    without_delete_markers(&upper_entry.value, options, &entry_path),
+                );
+            }
+        }
+    }
+}
+
+fn merge_node(base: &mut Node, upper: &Node, options: &MergeOptions, path: &Path) {
+    match (base, upper) {
+        (Node::Map(base_map), Node::Map(upper_map)) => {
+            merge_map(base_map, upper_map, options, path)
+        }
+        (Node::List(base_list), Node::List(upper_list)) => {
+            merge_list(base_list, upper_list, options, path)
+        }
+        (base, upper) => *base = without_delete_markers(upper, options, path),
+    }
+}
+
+fn merge_list(base: &mut List, upper: &List, options: &MergeOptions, path: &Path) {
+    match options.list_strategy(path) {
+        ListStrategy::Replace => {
+            *base = without_delete_markers_in_list(upper, options, path);
+        }
+        ListStrategy::Append => {
+            let upper = without_delete_markers_in_list(upper, options, path);
+            base.elements.extend(upper.elements);
+        }
+        ListStrategy::MergeByKey(field) => {
+            for upper_element in &upper.elements {
+                if options.is_delete_marker(upper_element) {
+                    continue;
+                }
+                let matching_index = merge_key(upper_element, field).and_then(|upper_key| {
+                    base.elements
+                        .iter()
+                        .position(|base_element| merge_key(base_element, field) == Some(upper_key))
+                });
+                match matching_index {
+                    Some(i) => merge_node(
+                        &mut base.elements[i],
+                        upper_element,
+                        options,
+                        &path.with_index(i),
+                    ),
+                    None => {
+                        let i = base.elements.len();
+                        base.elements.push(without_delete_markers(
+                            upper_element,
+                            options,
+                            &path.with_index(i),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the value of `field` if `node` is a map
+/// and `field` is an atom.
+fn merge_key<'a>(node: &'a Node, field: &Identifier) -> Option<&'a str> {
+    let Node::Map(map) = node else {
+        return None;
+    };
+    match map.get(field)? {
+        Node::Atom(atom) => Some(&atom.value),
+        _ => None,
+    }
+}
+
+/// Nodes introduced by an upper layer may still contain delete markers,
+/// which have nothing to delete, so they are dropped.
+fn without_delete_markers(node: &Node, options: &MergeOptions, path: &Path) -> Node {
+    match node {
+        Node::Map(map) => {
+            let mut clean = Map::default();
+            merge_map(&mut clean, map, options, path);
+            Node::Map(clean)
+        }
+        Node::List(list) => Node::List(without_delete_markers_in_list(list, options, path)),
+        Node::Atom(_) => node.clone(),
+    }
+}
+
+fn without_delete_markers_in_list(list: &List, options: &MergeOptions, path: &Path) -> List {
+    List {
+        elements: list
+            .elements
+            .iter()
+            .filter(|element| !options.is_delete_marker(element))
+            .enumerate()
+            .map(|(i, element)| without_delete_markers(element, options, &path.with_index(i)))
+            .collect(),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::tree::*;
+use crate::{escape::*, path::*, tree::*};
 
 mod non_whitespace_tracker;
 use non_whitespace_tracker::*;
@@ -120,6 +120,44 @@ pub enum ParseError {
 /// assert_eq!(expected, map);
 /// ```
 pub fn parse_doc(src: &str) -> Result<Map, ParseError> {
+    parse_doc_with(src, &mut |_, _| {})
+}
+
+/// Returns the byte position of the value at `path` in `src`
+/// (i.e., of its opening `"`, `{` or `[`),
+/// or `None` if `src` is not a valid document or has no such value.
+pub(crate) fn value_position(src: &str, path: &Path) -> Option<usize> {
+    let mut position = None;
+    parse_doc_with(src, &mut |stack, i| {
+        if position.is_none() && is_at(stack, path) {
+            position = Some(i);
+        }
+    })
+    .ok()?;
+    position
+}
+
+/// Returns `true` if a value pushed onto `stack` is located at `path`.
+fn is_at(stack: &[Unfinished], path: &Path) -> bool {
+    stack.len() == path.segments.len()
+        && stack
+            .iter()
+            .zip(&path.segments)
+            .all(|(unfinished, segment)| match (unfinished, segment) {
+                (Unfinished::Map(map), PathSegment::Key(key)) => *map.pending_entry.key == **key,
+                (Unfinished::List(list), PathSegment::Index(index)) => {
+                    list.elements.len() == *index
+                }
+                _ => false,
+            })
+}
+
+/// Parses a YSCL document, calling `on_value` with the stack
+/// and the byte position of each value just before it is pushed.
+fn parse_doc_with<F>(src: &str, on_value: &mut F) -> Result<Map, ParseError>
+where
+    F: FnMut(&[Unfinished], usize),
+{
     let mut stack = vec![Unfinished::Map(UnfinishedMap {
         entries: vec![],
         pending_entry: UnfinishedMapEntry::empty(),
@@ -128,7 +166,7 @@ pub fn parse_doc(src: &str) -> Result<Map, ParseError> {
 
     while let Some((i, c)) = remaining.next() {
         if let Continuation::Return(return_val) =
-            handle_character(&mut stack, i, c, &mut remaining)?
+            handle_character(&mut stack, i, c, &mut remaining, on_value)?
         {
             return Ok(return_val);
         }
@@ -143,14 +181,16 @@ enum Continuation<T> {
     Continue,
 }
 
-fn handle_character<I>(
+fn handle_character<I, F>(
     stack: &mut Vec<Unfinished>,
     i: usize,
     c: char,
     remaining: &mut NonWhiteSpaceTracker<I>,
+    on_value: &mut F,
 ) -> Result<Continuation<Map>, ParseError>
 where
     I: Iterator<Item = (usize, char)>,
+    F: FnMut(&[Unfinished], usize),
 {
     match stack.last_mut().expect("Stack should never be empty") {
         Unfinished::AtomValue(atom_value) => match c {
@@ -165,49 +205,7 @@ where
                     return Ok(Continuation::Return(return_val));
                 }
             }
-            '\\' => {
-                let Some((i_of_c_after_backslash, c_after_backslash)) = remaining.next() else {
-                    return Err(ParseError::UnexpectedEoi);
-                };
-                match c_after_backslash {
-                    '\\' | '"' => atom_value.push(c_after_backslash),
-                    'n' => atom_value.push('\n'),
-                    'u' => {
-                        let mut hex = String::with_capacity(6);
-                        for _ in 0..5 {
-                            let Some((hex_i, hex_c)) = remaining.next() else {
-                                return Err(ParseError::UnexpectedEoi);
-                            };
-                            if !hex_c.is_ascii_hexdigit() {
-                                return Err(ParseError::UnexpectedChar(hex_c, hex_i));
-                            }
-                            hex.push(hex_c);
-                        }
-                        let (last_hex_i, last_hex_c) =
-                            if let Some((last_hex_i, last_hex_c)) = remaining.next() {
-                                if !last_hex_c.is_ascii_hexdigit() {
-                                    return Err(ParseError::UnexpectedChar(last_hex_c, last_hex_i));
-                                }
-                                hex.push(last_hex_c);
-                                (last_hex_i, last_hex_c)
-                            } else {
-                                return Err(ParseError::UnexpectedEoi);
-                            };
-                        let codepoint =
-                            u32::from_str_radix(&hex, 16).expect("Hex code should always be valid");
-                        let Some(encoded_char) = std::char::from_u32(codepoint) else {
-                            return Err(ParseError::UnexpectedChar(last_hex_c, last_hex_i));
-                        };
-                        atom_value.push(encoded_char);
-                    }
-                    _ => {
-                        return Err(ParseError::UnexpectedChar(
-                            c_after_backslash,
-                            i_of_c_after_backslash,
-                        ))
-                    }
-                }
-            }
+            '\\' => atom_value.push(unescape_sequence(remaining)?),
             _other_char => atom_value.push(c),
         },
 
@@ -287,12 +285,14 @@ where
                 if !pending_entry.has_equal {
                     return Err(ParseError::UnexpectedChar(c, i));
                 }
+                on_value(stack, i);
                 stack.push(Unfinished::AtomValue("".to_string()));
             }
             '{' => {
                 if !pending_entry.has_equal {
                     return Err(ParseError::UnexpectedChar(c, i));
                 }
+                on_value(stack, i);
                 stack.push(Unfinished::Map(UnfinishedMap {
                     entries: vec![],
                     pending_entry: UnfinishedMapEntry::empty(),
@@ -302,6 +302,7 @@ where
                 if !pending_entry.has_equal {
                     return Err(ParseError::UnexpectedChar(c, i));
                 }
+                on_value(stack, i);
                 stack.push(Unfinished::List(UnfinishedList { elements: vec![] }));
             }
             '/' if remaining.non_whitespace_on_current_line() == 1 => {
@@ -310,7 +311,7 @@ where
                 };
                 match next_c {
                     '/' => {
-                        while let Some((_, next_c)) = remaining.next() {
+                        for (_, next_c) in remaining.by_ref() {
                             if next_c == '\n' {
                                 break;
                             }
@@ -334,15 +335,18 @@ where
                 }
             }
             '"' if remaining.non_whitespace_on_current_line() == 1 => {
+                on_value(stack, i);
                 stack.push(Unfinished::AtomValue("".to_string()));
             }
             '{' if remaining.non_whitespace_on_current_line() == 1 => {
+                on_value(stack, i);
                 stack.push(Unfinished::Map(UnfinishedMap {
                     entries: vec![],
                     pending_entry: UnfinishedMapEntry::empty(),
                 }));
             }
             '[' if remaining.non_whitespace_on_current_line() == 1 => {
+                on_value(stack, i);
                 stack.push(Unfinished::List(UnfinishedList { elements: vec![] }));
             }
             '/' if remaining.non_whitespace_on_current_line() == 1 => {
@@ -351,7 +355,7 @@ where
                 };
                 match next_c {
                     '/' => {
-                        while let Some((_, next_c)) = remaining.next() {
+                        for (_, next_c) in remaining.by_ref() {
                             if next_c == '\n' {
                                 break;
                             }
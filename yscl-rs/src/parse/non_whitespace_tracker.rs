@@ -26,9 +26,7 @@ where
     type Item = (usize, char);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some((i, c)) = self.iter.next() else {
-            return None;
-        };
+        let (i, c) = self.iter.next()?;
         if c == '\n' {
             self.non_whitespace_on_current_line = 0;
         } else if !c.is_whitespace() {
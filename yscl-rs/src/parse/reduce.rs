@@ -1,6 +1,6 @@
 use super::*;
 
-pub fn reduce_stack(stack: &mut Vec<Unfinished>, top: Node) -> Result<Option<Map>, ()> {
+pub fn reduce_stack(stack: &mut [Unfinished], top: Node) -> Result<Option<Map>, ()> {
     match stack.last_mut() {
         None => match top {
             Node::Map(top) => Ok(Some(top)),
@@ -0,0 +1,376 @@
+//! Patch documents, similar to [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)
+//! JSON Patch, but written in YSCL.
+//!
+//! A patch document has a single `operations` key containing a list of
+//! operations.
+//! Each operation is a map with an `op` key and the fields that
+//! operation requires:
+//!
+//! | `op`      | Fields            | Effect                                                           |
+//! |-----------|-------------------|------------------------------------------------------------------|
+//! | `add`     | `path`, `value`   | Sets a map entry, or inserts a list element at the given index.  |
+//! | `remove`  | `path`            | Removes an existing map entry or list element.                   |
+//! | `replace` | `path`, `value`   | Replaces an existing node.                                       |
+//! | `move`    | `from`, `path`    | Removes the node at `from` and adds it at `path`.                |
+//! | `copy`    | `from`, `path`    | Adds a copy of the node at `from` at `path`.                     |
+//! | `test`    | `path`, `value`   | Fails unless the node at `path` equals `value`.                  |
+//!
+//! Paths use the syntax of [`Path`] (e.g., `dependencies.foo` or `licenses[1]`).
+//! To append to a list, `add` at the index equal to the list's length.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let mut map = parse_doc(r#"
+//! version = "1.0.0"
+//! licenses = [
+//!     "MIT"
+//! ]
+//! "#).unwrap();
+//!
+//! let patch = parse_patch(r#"
+//! operations = [
+//!     {
+//!         op = "test"
+//!         path = "version"
+//!         value = "1.0.0"
+//!     }
+//!     {
+//!         op = "replace"
+//!         path = "version"
+//!         value = "1.1.0"
+//!     }
+//!     {
+//!         op = "add"
+//!         path = "licenses[1]"
+//!         value = "APACHE"
+//!     }
+//! ]
+//! "#).unwrap();
+//!
+//! apply_patch(&mut map, &patch).unwrap();
+//! assert_eq!(
+//!     Node::Map(map),
+//!     yscl_node!({ version = "1.1.0", licenses = ["MIT", "APACHE"] }),
+//! );
+//! ```
+
+use crate::{parse::*, path::*, tree::*, typed::*};
+
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Patch {
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PatchOperation {
+    Add { path: Path, value: Node },
+    Remove { path: Path },
+    Replace { path: Path, value: Node },
+    Move { from: Path, path: Path },
+    Copy { from: Path, path: Path },
+    Test { path: Path, value: Node },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchParseError {
+    Syntax(ParseError),
+    /// A required field is missing or malformed.
+    Field(AccessError),
+    /// The path of the `op` field and its unrecognized value.
+    UnknownOp(Path, String),
+}
+
+/// An error that occurred while applying a patch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchError {
+    /// The index of the operation that failed.
+    pub index: usize,
+    pub kind: PatchErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchErrorKind {
+    /// Nothing exists at the path.
+    Missing(Path),
+    /// The path's last segment is a key but its parent is not a map,
+    /// or the segment is an index but its parent is not a list.
+    WrongKind(Path),
+    /// The path and the length of the list it points into.
+    IndexOutOfBounds(Path, usize),
+    /// The node at the path did not equal the expected value.
+    TestFailed(Path),
+    /// A `move` operation tried to move a node into one of its own descendants.
+    MoveIntoDescendant,
+    /// Only `test` may target the root.
+    RootTarget,
+}
+
+/// Parses a patch document.
+pub fn parse_patch(src: &str) -> Result<Patch, PatchParseError> {
+    let map = parse_doc(src).map_err(PatchParseError::Syntax)?;
+    Patch::from_map(&map)
+}
+
+/// Applies every operation in order.
+///
+/// The patch is atomic: if any operation fails,
+/// `map` is left unchanged and the index of the failed operation is returned.
+pub fn apply_patch(map: &mut Map, patch: &Patch) -> Result<(), PatchError> {
+    let mut root = Node::Map(map.clone());
+    for (index, operation) in patch.operations.iter().enumerate() {
+        apply_operation(&mut root, operation).map_err(|kind| PatchError { index, kind })?;
+    }
+    *map = root.map().expect("Root should remain a map");
+    Ok(())
+}
+
+impl Patch {
+    pub fn from_map(map: &Map) -> Result<Self, PatchParseError> {
+        let operation_count = map
+            .get_list("operations")
+            .map_err(PatchParseError::Field)?
+            .len();
+        let operations = (0..operation_count)
+            .map(|i| parse_operation(map, &format!("operations[{}]", i)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { operations })
+    }
+
+    /// Returns the YSCL representation of the patch,
+    /// suitable for [`Patch::from_map`].
+    pub fn to_map(&self) -> Map {
+        let operations = self
+            .operations
+            .iter()
+            .map(PatchOperation::to_node)
+            .collect();
+        Map {
+            entries: vec![MapEntry {
+                key: identifier("operations"),
+                value: Node::List(List {
+                    elements: operations,
+                }),
+            }],
+        }
+    }
+}
+
+impl PatchOperation {
+    pub fn op_name(&self) -> &'static str {
+        match self {
+            PatchOperation::Add { .. } => "add",
+            PatchOperation::Remove { .. } => "remove",
+            PatchOperation::Replace { .. } => "replace",
+            PatchOperation::Move { .. } => "move",
+            PatchOperation::Copy { .. } => "copy",
+            PatchOperation::Test { .. } => "test",
+        }
+    }
+
+    fn to_node(&self) -> Node {
+        let mut map = Map::default();
+        map.insert(identifier("op"), Node::from(self.op_name()));
+        match self {
+            PatchOperation::Move { from, path } | PatchOperation::Copy { from, path } => {
+                map.insert(identifier("from"), Node::from(from.to_string()));
+                map.insert(identifier("path"), Node::from(path.to_string()));
+            }
+            PatchOperation::Remove { path } => {
+                map.insert(identifier("path"), Node::from(path.to_string()));
+            }
+            PatchOperation::Add { path, value }
+            | PatchOperation::Replace { path, value }
+            | PatchOperation::Test { path, value } => {
+                map.insert(identifier("path"), Node::from(path.to_string()));
+                map.insert(identifier("value"), value.clone());
+            }
+        }
+        Node::Map(map)
+    }
+}
+
+fn identifier(s: &str) -> Identifier {
+    Identifier::new(s.to_owned()).expect("Key should be a valid identifier")
+}
+
+fn parse_operation(map: &Map, prefix: &str) -> Result<PatchOperation, PatchParseError> {
+    let field = |name: &str| format!("{}.{}", prefix, name);
+    let path_field = |name: &str| {
+        map.get_as::<Path>(&field(name))
+            .map_err(PatchParseError::Field)
+    };
+    let value_field = || {
+        map.get_node(&field("value"))
+            .map(NodeRef::to_owned)
+            .map_err(PatchParseError::Field)
+    };
+
+    let op = map
+        .get_as::<String>(&field("op"))
+        .map_err(PatchParseError::Field)?;
+    Ok(match op.as_str() {
+        "add" => PatchOperation::Add {
+            path: path_field("path")?,
+            value: value_field()?,
+        },
+        "remove" => PatchOperation::Remove {
+            path: path_field("path")?,
+        },
+        "replace" => PatchOperation::Replace {
+            path: path_field("path")?,
+            value: value_field()?,
+        },
+        "move" => PatchOperation::Move {
+            from: path_field("from")?,
+            path: path_field("path")?,
+        },
+        "copy" => PatchOperation::Copy {
+            from: path_field("from")?,
+            path: path_field("path")?,
+        },
+        "test" => PatchOperation::Test {
+            path: path_field("path")?,
+            value: value_field()?,
+        },
+        _ => {
+            return Err(PatchParseError::UnknownOp(
+                field("op").parse().expect("Field path should be valid"),
+                op,
+            ))
+        }
+    })
+}
+
+fn apply_operation(root: &mut Node, operation: &PatchOperation) -> Result<(), PatchErrorKind> {
+    match operation {
+        PatchOperation::Add { path, value } => add(root, path, value.clone()),
+        PatchOperation::Remove { path } => remove(root, path).map(std::mem::drop),
+        PatchOperation::Replace { path, value } => {
+            if path.is_root() {
+                return Err(PatchErrorKind::RootTarget);
+            }
+            let target = path
+                .resolve_mut(root)
+                .ok_or_else(|| PatchErrorKind::Missing(path.clone()))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if from == path {
+                return if path.resolve(root.as_ref()).is_some() {
+                    Ok(())
+                } else {
+                    Err(PatchErrorKind::Missing(path.clone()))
+                };
+            }
+            if from.is_prefix_of(path) {
+                return Err(PatchErrorKind::MoveIntoDescendant);
+            }
+            let value = remove(root, from)?;
+            add(root, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = from
+                .resolve(root.as_ref())
+                .ok_or_else(|| PatchErrorKind::Missing(from.clone()))?
+                .to_owned();
+            add(root, path, value)
+        }
+        PatchOperation::Test { path, value } => match path.resolve(root.as_ref()) {
+            Some(actual) if actual == value.as_ref() => Ok(()),
+            Some(_) => Err(PatchErrorKind::TestFailed(path.clone())),
+            None => Err(PatchErrorKind::Missing(path.clone())),
+        },
+    }
+}
+
+fn add(root: &mut Node, path: &Path, value: Node) -> Result<(), PatchErrorKind> {
+    let (parent, last) = split_last(path)?;
+    match (parent_mut(root, &parent)?, last) {
+        (Node::Map(map), PathSegment::Key(key)) => {
+            map.insert(key.clone(), value);
+            Ok(())
+        }
+        (Node::List(list), PathSegment::Index(index)) => {
+            let len = list.len();
+            list.insert(*index, value)
+                .map_err(|_| PatchErrorKind::IndexOutOfBounds(path.clone(), len))
+        }
+        _ => Err(PatchErrorKind::WrongKind(path.clone())),
+    }
+}
+
+fn remove(root: &mut Node, path: &Path) -> Result<Node, PatchErrorKind> {
+    let (parent, last) = split_last(path)?;
+    match (parent_mut(root, &parent)?, last) {
+        (Node::Map(map), PathSegment::Key(key)) => map
+            .remove(key)
+            .ok_or_else(|| PatchErrorKind::Missing(path.clone())),
+        (Node::List(list), PathSegment::Index(index)) => {
+            let len = list.len();
+            list.remove(*index)
+                .map_err(|_| PatchErrorKind::IndexOutOfBounds(path.clone(), len))
+        }
+        _ => Err(PatchErrorKind::WrongKind(path.clone())),
+    }
+}
+
+fn split_last(path: &Path) -> Result<(Path, &PathSegment), PatchErrorKind> {
+    let last = path.segments.last().ok_or(PatchErrorKind::RootTarget)?;
+    let parent = path.parent().expect("Non-root path should have a parent");
+    Ok((parent, last))
+}
+
+fn parent_mut<'a>(root: &'a mut Node, parent: &Path) -> Result<&'a mut Node, PatchErrorKind> {
+    parent
+        .resolve_mut(root)
+        .ok_or_else(|| PatchErrorKind::Missing(parent.clone()))
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation {} failed: ", self.index)?;
+        match &self.kind {
+            PatchErrorKind::Missing(path) => write!(f, "{} does not exist", path),
+            PatchErrorKind::WrongKind(path) => {
+                write!(f, "the parent of {} is the wrong kind of node", path)
+            }
+            PatchErrorKind::IndexOutOfBounds(path, len) => {
+                write!(f, "{} is out of bounds (list length is {})", path, len)
+            }
+            PatchErrorKind::TestFailed(path) => write!(f, "{} does not match", path),
+            PatchErrorKind::MoveIntoDescendant => {
+                write!(f, "cannot move a node into one of its descendants")
+            }
+            PatchErrorKind::RootTarget => write!(f, "only `test` may target the root"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl fmt::Display for PatchParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchParseError::Syntax(ParseError::UnexpectedChar(c, i)) => {
+                write!(f, "unexpected {:?} at byte {}", c, i)
+            }
+            PatchParseError::Syntax(ParseError::UnexpectedEoi) => {
+                write!(f, "unexpected end of input")
+            }
+            PatchParseError::Syntax(ParseError::DuplicateKey(key, i)) => {
+                write!(f, "duplicate key {:?} at byte {}", key, i)
+            }
+            PatchParseError::Field(err) => write!(f, "{}", err),
+            PatchParseError::UnknownOp(path, op) => {
+                write!(f, "{}: unknown operation {:?}", path, op)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchParseError {}
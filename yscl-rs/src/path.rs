@@ -0,0 +1,243 @@
+use crate::tree::*;
+
+use std::{fmt, str::FromStr};
+
+/// The location of a node within a YSCL document,
+/// expressed as a sequence of map keys and list indices
+/// starting from the root.
+///
+/// Paths are written with dots between keys and
+/// square brackets around list indices
+/// (e.g., `licenses[2].url`).
+/// The root path is written as the empty string.
+///
+/// ## Example
+/// ```rust
+/// use yscl::prelude::*;
+///
+/// let map = parse_doc(r#"
+/// licenses = [
+///     "MIT"
+///     {
+///         url = "https://example.com/LICENSE"
+///     }
+/// ]
+/// "#).unwrap();
+///
+/// let path: Path = "licenses[1].url".parse().unwrap();
+/// assert_eq!(
+///     path.resolve(NodeRef::Map(&map)).unwrap().atom().unwrap().value,
+///     "https://example.com/LICENSE",
+/// );
+/// assert_eq!(path.to_string(), "licenses[1].url");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    pub segments: Vec<PathSegment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(Identifier),
+    Index(usize),
+}
+
+/// Indices are counted in bytes, not chars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathParseError {
+    /// The character and its byte position.
+    UnexpectedChar(char, usize),
+    /// Unexpected end of input.
+    UnexpectedEoi,
+}
+
+impl Path {
+    pub fn root() -> Self {
+        Self { segments: vec![] }
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns a new path that points to the value of `key`
+    /// in the map located at `self`.
+    pub fn with_key(&self, key: Identifier) -> Self {
+        self.with_segment(PathSegment::Key(key))
+    }
+
+    /// Returns a new path that points to the `index`th element
+    /// of the list located at `self`.
+    pub fn with_index(&self, index: usize) -> Self {
+        self.with_segment(PathSegment::Index(index))
+    }
+
+    pub fn with_segment(&self, segment: PathSegment) -> Self {
+        let mut segments = Vec::with_capacity(self.segments.len() + 1);
+        segments.extend(self.segments.iter().cloned());
+        segments.push(segment);
+        Self { segments }
+    }
+
+    /// Returns a new path that points to `relative`,
+    /// interpreted relative to the node located at `self`.
+    pub fn join(&self, relative: &Path) -> Self {
+        let mut segments = Vec::with_capacity(self.segments.len() + relative.segments.len());
+        segments.extend(self.segments.iter().cloned());
+        segments.extend(relative.segments.iter().cloned());
+        Self { segments }
+    }
+
+    /// Returns the path of the node containing `self`,
+    /// or `None` if `self` is the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, rest) = self.segments.split_last()?;
+        Some(Self {
+            segments: rest.to_vec(),
+        })
+    }
+
+    /// Returns `true` if `self` is equal to `other`
+    /// or `other` is nested inside `self`.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.segments.starts_with(&self.segments)
+    }
+
+    /// Follows the path starting from `root`.
+    /// Returns `None` if any segment is missing
+    /// or is applied to a node of the wrong kind.
+    pub fn resolve<'a>(&self, root: NodeRef<'a>) -> Option<NodeRef<'a>> {
+        self.segments
+            .iter()
+            .try_fold(root, |node, segment| match (segment, node) {
+                (PathSegment::Key(key), NodeRef::Map(map)) => map.get(key).map(Node::as_ref),
+                (PathSegment::Index(index), NodeRef::List(list)) => {
+                    list.elements.get(*index).map(Node::as_ref)
+                }
+                _ => None,
+            })
+    }
+
+    /// Like [`Path::resolve`], but returns a mutable reference.
+    pub fn resolve_mut<'a>(&self, root: &'a mut Node) -> Option<&'a mut Node> {
+        self.segments
+            .iter()
+            .try_fold(root, |node, segment| match (segment, node) {
+                (PathSegment::Key(key), Node::Map(map)) => map.get_mut(key),
+                (PathSegment::Index(index), Node::List(list)) => list.elements.get_mut(*index),
+                _ => None,
+            })
+    }
+}
+
+impl From<Identifier> for PathSegment {
+    fn from(key: Identifier) -> Self {
+        PathSegment::Key(key)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+impl FromIterator<PathSegment> for Path {
+    fn from_iter<T: IntoIterator<Item = PathSegment>>(iter: T) -> Self {
+        Self {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", &**key)?,
+                PathSegment::Key(key) => write!(f, ".{}", &**key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathParseError::UnexpectedChar(c, i) => write!(f, "unexpected {:?} at byte {}", c, i),
+            PathParseError::UnexpectedEoi => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl std::error::Error for PathParseError {}
+
+impl FromStr for Path {
+    type Err = PathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut chars = s.char_indices().peekable();
+
+        while let Some(&(i, c)) = chars.peek() {
+            match c {
+                '[' => {
+                    chars.next();
+                    let mut digits = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, ']')) if !digits.is_empty() => break,
+                            Some((_, digit)) if digit.is_ascii_digit() => digits.push(digit),
+                            Some((j, other)) => {
+                                return Err(PathParseError::UnexpectedChar(other, j))
+                            }
+                            None => return Err(PathParseError::UnexpectedEoi),
+                        }
+                    }
+                    let index = digits
+                        .parse()
+                        .map_err(|_| PathParseError::UnexpectedChar(']', i))?;
+                    segments.push(PathSegment::Index(index));
+                }
+                '.' if !segments.is_empty() => {
+                    chars.next();
+                    segments.push(PathSegment::Key(parse_key(&mut chars)?));
+                }
+                _ if segments.is_empty() => {
+                    segments.push(PathSegment::Key(parse_key(&mut chars)?));
+                }
+                _ => return Err(PathParseError::UnexpectedChar(c, i)),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+fn parse_key<I>(chars: &mut std::iter::Peekable<I>) -> Result<Identifier, PathParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut key = String::new();
+    while let Some(&(i, c)) = chars.peek() {
+        if !(c.is_ascii_alphanumeric() || c == '_') {
+            break;
+        }
+        if key.is_empty() && c.is_ascii_digit() {
+            return Err(PathParseError::UnexpectedChar(c, i));
+        }
+        key.push(c);
+        chars.next();
+    }
+
+    if key.is_empty() {
+        return match chars.peek() {
+            Some(&(i, c)) => Err(PathParseError::UnexpectedChar(c, i)),
+            None => Err(PathParseError::UnexpectedEoi),
+        };
+    }
+
+    Ok(Identifier::new(key).expect("Key should only contain identifier characters"))
+}
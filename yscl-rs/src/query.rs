@@ -0,0 +1,508 @@
+//! A JSONPath-style query language for YSCL trees.
+//!
+//! ## Syntax
+//!
+//! | Syntax            | Meaning                                                   |
+//! |-------------------|-----------------------------------------------------------|
+//! | `$`               | The root (optional).                                      |
+//! | `.key`, `["key"]` | The value of `key`.                                       |
+//! | `.*`, `[*]`       | Every map value or list element.                          |
+//! | `..key`, `..*`    | Like `.key` or `.*`, applied to every descendant-or-self. |
+//! | `[2]`, `[-1]`     | A list element. Negative indices count from the end.      |
+//! | `[1:3]`, `[:-1]`  | A range of list elements (end exclusive).                 |
+//! | `[?(@.key)]`      | Every child for which `@.key` exists.                     |
+//! | `[?(@ == "x")]`   | Every child that is an atom with value `"x"`.             |
+//!
+//! Filters support the comparison operators
+//! `==`, `!=`, `^=` (starts with), `$=` (ends with), and `*=` (contains).
+//! The left-hand side of a filter is `@` (the child being tested),
+//! optionally followed by a relative path (e.g., `@.version` or `@[0]`).
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let map = parse_doc(r#"
+//! licenses = [
+//!     "MIT"
+//!     {
+//!         url = "https://example.com/LICENSE"
+//!     }
+//! ]
+//! dependencies = {
+//!     foo = {
+//!         version = "1.2.0"
+//!     }
+//!     bar = {
+//!         version = "2.0.0"
+//!     }
+//! }
+//! "#).unwrap();
+//!
+//! let urls = query(&map, "$.licenses..url").unwrap();
+//! assert_eq!(urls.len(), 1);
+//! assert_eq!(urls[0].path.to_string(), "licenses[1].url");
+//!
+//! let v1_deps = query(&map, r#"$.dependencies[?(@.version ^= "1.")]"#).unwrap();
+//! assert_eq!(v1_deps.len(), 1);
+//! assert_eq!(v1_deps[0].path.to_string(), "dependencies.foo");
+//! ```
+
+use crate::{path::*, tree::*};
+
+use std::{fmt, iter::Peekable, str::CharIndices, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Query {
+    pub segments: Vec<QuerySegment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QuerySegment {
+    /// Applies the selector to the current nodes.
+    Child(QuerySelector),
+    /// Applies the selector to the current nodes and all their descendants.
+    Descendant(QuerySelector),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QuerySelector {
+    Key(String),
+    Wildcard,
+    Index(isize),
+    Slice(Option<isize>, Option<isize>),
+    Filter(QueryFilter),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueryFilter {
+    /// The path of the tested node, relative to the child being filtered.
+    pub subject: Path,
+    /// If this is `None`, the filter only checks that `subject` exists.
+    pub comparison: Option<(QueryComparison, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QueryComparison {
+    Eq,
+    Ne,
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueryMatch<'a> {
+    pub path: Path,
+    pub node: NodeRef<'a>,
+}
+
+/// Indices are counted in bytes, not chars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryParseError {
+    /// The character and its byte position.
+    UnexpectedChar(char, usize),
+    /// Unexpected end of input.
+    UnexpectedEoi,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryParseError::UnexpectedChar(c, i) => write!(f, "unexpected {:?} at byte {}", c, i),
+            QueryParseError::UnexpectedEoi => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+/// Parses `src` as a [`Query`] and evaluates it against `root`.
+pub fn query<'a>(root: &'a Map, src: &str) -> Result<Vec<QueryMatch<'a>>, QueryParseError> {
+    let query: Query = src.parse()?;
+    Ok(query.select(NodeRef::Map(root)))
+}
+
+impl Query {
+    /// Returns every node matched by the query, in document order.
+    pub fn select<'a>(&self, root: NodeRef<'a>) -> Vec<QueryMatch<'a>> {
+        let mut current = vec![QueryMatch {
+            path: Path::root(),
+            node: root,
+        }];
+
+        for segment in &self.segments {
+            let mut next = vec![];
+            for matched in &current {
+                match segment {
+                    QuerySegment::Child(selector) => selector.select(matched, &mut next),
+                    QuerySegment::Descendant(selector) => {
+                        for (relative_path, node) in matched.node.depth_first() {
+                            let descendant = QueryMatch {
+                                path: matched.path.join(&relative_path),
+                                node,
+                            };
+                            selector.select(&descendant, &mut next);
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        current
+    }
+}
+
+impl QuerySelector {
+    fn select<'a>(&self, parent: &QueryMatch<'a>, out: &mut Vec<QueryMatch<'a>>) {
+        match (self, parent.node) {
+            (QuerySelector::Key(key), NodeRef::Map(map)) => {
+                if let Some(entry) = map.entries.iter().find(|entry| *entry.key == **key) {
+                    out.push(QueryMatch {
+                        path: parent.path.with_key(entry.key.clone()),
+                        node: entry.value.as_ref(),
+                    });
+                }
+            }
+            (QuerySelector::Wildcard, _) => out.extend(children(parent)),
+            (QuerySelector::Index(index), NodeRef::List(list)) => {
+                if let Some(index) = normalize_index(*index, list.elements.len()) {
+                    if index < list.elements.len() {
+                        out.push(QueryMatch {
+                            path: parent.path.with_index(index),
+                            node: list.elements[index].as_ref(),
+                        });
+                    }
+                }
+            }
+            (QuerySelector::Slice(start, end), NodeRef::List(list)) => {
+                let len = list.elements.len();
+                let start = start
+                    .map(|start| normalize_index(start, len).unwrap_or(0))
+                    .unwrap_or(0)
+                    .min(len);
+                let end = end
+                    .map(|end| normalize_index(end, len).unwrap_or(0))
+                    .unwrap_or(len)
+                    .min(len);
+                for index in start..end {
+                    out.push(QueryMatch {
+                        path: parent.path.with_index(index),
+                        node: list.elements[index].as_ref(),
+                    });
+                }
+            }
+            (QuerySelector::Filter(filter), _) => out.extend(
+                children(parent)
+                    .into_iter()
+                    .filter(|child| filter.matches(child.node)),
+            ),
+            _ => {}
+        }
+    }
+}
+
+impl QueryFilter {
+    pub fn matches(&self, node: NodeRef) -> bool {
+        let Some(subject) = self.subject.resolve(node) else {
+            return false;
+        };
+        let Some((comparison, expected)) = &self.comparison else {
+            return true;
+        };
+        let Some(atom) = subject.atom() else {
+            return false;
+        };
+        let actual = atom.value.as_str();
+        match comparison {
+            QueryComparison::Eq => actual == expected,
+            QueryComparison::Ne => actual != expected,
+            QueryComparison::StartsWith => actual.starts_with(expected.as_str()),
+            QueryComparison::EndsWith => actual.ends_with(expected.as_str()),
+            QueryComparison::Contains => actual.contains(expected.as_str()),
+        }
+    }
+}
+
+/// Converts a possibly negative index into a non-negative one.
+/// Returns `None` if the index is before the start of the list.
+fn normalize_index(index: isize, len: usize) -> Option<usize> {
+    if index >= 0 {
+        Some(index as usize)
+    } else {
+        len.checked_sub(index.unsigned_abs())
+    }
+}
+
+fn children<'a>(parent: &QueryMatch<'a>) -> Vec<QueryMatch<'a>> {
+    match parent.node {
+        NodeRef::Atom(_) => vec![],
+        NodeRef::Map(map) => map
+            .entries
+            .iter()
+            .map(|entry| QueryMatch {
+                path: parent.path.with_key(entry.key.clone()),
+                node: entry.value.as_ref(),
+            })
+            .collect(),
+        NodeRef::List(list) => list
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| QueryMatch {
+                path: parent.path.with_index(index),
+                node: element.as_ref(),
+            })
+            .collect(),
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = QueryParser {
+            chars: s.char_indices().peekable(),
+        };
+        parser.parse_query()
+    }
+}
+
+struct QueryParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl QueryParser<'_> {
+    fn parse_query(&mut self) -> Result<Query, QueryParseError> {
+        let mut segments = vec![];
+
+        if self.peek_char() == Some('$') {
+            self.chars.next();
+        } else if self.peek_char().is_some_and(is_key_char) {
+            segments.push(QuerySegment::Child(QuerySelector::Key(self.parse_key()?)));
+        }
+
+        while let Some((i, c)) = self.chars.next() {
+            match c {
+                '.' if self.peek_char() == Some('.') => {
+                    self.chars.next();
+                    let selector = if self.peek_char() == Some('[') {
+                        self.chars.next();
+                        self.parse_bracketed_selector()?
+                    } else {
+                        self.parse_dotted_selector()?
+                    };
+                    segments.push(QuerySegment::Descendant(selector));
+                }
+                '.' => segments.push(QuerySegment::Child(self.parse_dotted_selector()?)),
+                '[' => segments.push(QuerySegment::Child(self.parse_bracketed_selector()?)),
+                _ => return Err(QueryParseError::UnexpectedChar(c, i)),
+            }
+        }
+
+        Ok(Query { segments })
+    }
+
+    /// Parses the part after a `.` or `..`.
+    fn parse_dotted_selector(&mut self) -> Result<QuerySelector, QueryParseError> {
+        if self.peek_char() == Some('*') {
+            self.chars.next();
+            return Ok(QuerySelector::Wildcard);
+        }
+        Ok(QuerySelector::Key(self.parse_key()?))
+    }
+
+    /// Parses the part after a `[`, including the closing `]`.
+    fn parse_bracketed_selector(&mut self) -> Result<QuerySelector, QueryParseError> {
+        self.skip_spaces();
+        let selector = match self.peek_char() {
+            Some('*') => {
+                self.chars.next();
+                QuerySelector::Wildcard
+            }
+            Some('"') => QuerySelector::Key(self.parse_string()?),
+            Some('?') => {
+                self.chars.next();
+                self.expect('(')?;
+                let filter = self.parse_filter()?;
+                self.expect(')')?;
+                QuerySelector::Filter(filter)
+            }
+            _ => {
+                let start = self.parse_optional_int()?;
+                self.skip_spaces();
+                if self.peek_char() == Some(':') {
+                    self.chars.next();
+                    self.skip_spaces();
+                    let end = self.parse_optional_int()?;
+                    QuerySelector::Slice(start, end)
+                } else if let Some(index) = start {
+                    QuerySelector::Index(index)
+                } else {
+                    return Err(self.unexpected());
+                }
+            }
+        };
+        self.skip_spaces();
+        self.expect(']')?;
+        Ok(selector)
+    }
+
+    fn parse_filter(&mut self) -> Result<QueryFilter, QueryParseError> {
+        self.skip_spaces();
+        self.expect('@')?;
+
+        let mut subject = Path::root();
+        loop {
+            match self.peek_char() {
+                Some('.') => {
+                    self.chars.next();
+                    let Some((i, c)) = self.chars.peek().copied() else {
+                        return Err(QueryParseError::UnexpectedEoi);
+                    };
+                    let Ok(key) = Identifier::new(self.parse_key()?) else {
+                        return Err(QueryParseError::UnexpectedChar(c, i));
+                    };
+                    subject = subject.with_key(key);
+                }
+                Some('[') => {
+                    self.chars.next();
+                    let Some((i, c)) = self.chars.peek().copied() else {
+                        return Err(QueryParseError::UnexpectedEoi);
+                    };
+                    let Some(index) = self.parse_optional_int()? else {
+                        return Err(self.unexpected());
+                    };
+                    let Ok(index) = usize::try_from(index) else {
+                        return Err(QueryParseError::UnexpectedChar(c, i));
+                    };
+                    self.expect(']')?;
+                    subject = subject.with_index(index);
+                }
+                _ => break,
+            }
+        }
+
+        self.skip_spaces();
+        let comparison = match self.peek_char() {
+            Some(')') => None,
+            Some(_) => {
+                let comparison = self.parse_comparison()?;
+                self.skip_spaces();
+                let value = self.parse_string()?;
+                self.skip_spaces();
+                Some((comparison, value))
+            }
+            None => return Err(QueryParseError::UnexpectedEoi),
+        };
+
+        Ok(QueryFilter {
+            subject,
+            comparison,
+        })
+    }
+
+    fn parse_comparison(&mut self) -> Result<QueryComparison, QueryParseError> {
+        let (i, first) = self.chars.next().ok_or(QueryParseError::UnexpectedEoi)?;
+        let comparison = match first {
+            '=' => QueryComparison::Eq,
+            '!' => QueryComparison::Ne,
+            '^' => QueryComparison::StartsWith,
+            '$' => QueryComparison::EndsWith,
+            '*' => QueryComparison::Contains,
+            _ => return Err(QueryParseError::UnexpectedChar(first, i)),
+        };
+        self.expect('=')?;
+        Ok(comparison)
+    }
+
+    fn parse_key(&mut self) -> Result<String, QueryParseError> {
+        let mut key = String::new();
+        while let Some(c) = self.peek_char() {
+            if !is_key_char(c) {
+                break;
+            }
+            key.push(c);
+            self.chars.next();
+        }
+        if key.is_empty() {
+            return Err(self.unexpected());
+        }
+        Ok(key)
+    }
+
+    fn parse_optional_int(&mut self) -> Result<Option<isize>, QueryParseError> {
+        let Some((start, _)) = self.chars.peek().copied() else {
+            return Err(QueryParseError::UnexpectedEoi);
+        };
+        let mut digits = String::new();
+        if self.peek_char() == Some('-') {
+            digits.push('-');
+            self.chars.next();
+        }
+        while let Some(c) = self.peek_char() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(c);
+            self.chars.next();
+        }
+        match digits.as_str() {
+            "" => Ok(None),
+            "-" => Err(self.unexpected()),
+            _ => digits.parse().map(Some).map_err(|_| {
+                QueryParseError::UnexpectedChar(digits.chars().next().unwrap(), start)
+            }),
+        }
+    }
+
+    /// Parses a double-quoted string.
+    /// The escape sequences `\"`, `\\`, and `\n` are supported.
+    fn parse_string(&mut self) -> Result<String, QueryParseError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c @ ('"' | '\\'))) => value.push(c),
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((i, c)) => return Err(QueryParseError::UnexpectedChar(c, i)),
+                    None => return Err(QueryParseError::UnexpectedEoi),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(QueryParseError::UnexpectedEoi),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), QueryParseError> {
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(QueryParseError::UnexpectedChar(c, i)),
+            None => Err(QueryParseError::UnexpectedEoi),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek_char() == Some(' ') {
+            self.chars.next();
+        }
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn unexpected(&mut self) -> QueryParseError {
+        match self.chars.peek() {
+            Some(&(i, c)) => QueryParseError::UnexpectedChar(c, i),
+            None => QueryParseError::UnexpectedEoi,
+        }
+    }
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
@@ -0,0 +1,514 @@
+//! Keeping secret values out of logs.
+//!
+//! There are three complementary tools:
+//!
+//! - [`Secret<T>`] wraps a value read with the typed accessors
+//!   (e.g., `map.get_as::<Secret<String>>("api_token")`).
+//!   Its `Debug` and `Display` print [`REDACTED`],
+//!   the atom's value is redacted from any [`AccessError`],
+//!   and the value is only available through [`Secret::expose`].
+//! - [`SecretPolicy`] decides which atoms of a tree are secret,
+//!   by key name (e.g., `*_token`) or by path pattern (e.g., `database.password`).
+//!   It can produce a redacted copy of a tree (safe to `Debug` or print),
+//!   redact diff changes before rendering them,
+//!   and redact values from access errors.
+//! - [`Redact::redacted`] wraps a tree (or shared tree), a diff [`Change`],
+//!   or an [`AccessError`], so that its `Debug` output
+//!   (and `Display` output, if it has one) redacts the atoms a policy marks secret.
+//!   The value itself is not modified, and its own `Debug` output is not redacted.
+//!
+//! Secrets are redacted by replacing the atom's value with [`REDACTED`].
+//! Maps and lists under a secret key keep their shape,
+//! but every atom inside them is redacted.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let old = parse_doc(r#"
+//! name = "app"
+//! github_token = "ghp_old"
+//! "#).unwrap();
+//! let new = parse_doc(r#"
+//! name = "app2"
+//! github_token = "ghp_new"
+//! "#).unwrap();
+//!
+//! let token = new.get_as::<Secret<String>>("github_token").unwrap();
+//! assert_eq!(format!("{:?}", token), "[REDACTED]");
+//! assert_eq!(token.expose(), "ghp_new");
+//!
+//! let policy = SecretPolicy::conventional();
+//! let debug = format!("{:?}", new.redacted(&policy));
+//! assert!(debug.contains("app2"));
+//! assert!(!debug.contains("ghp_new"));
+//!
+//! assert_eq!(
+//!     Node::Map(policy.redact(&new)),
+//!     yscl_node!({ name = "app2", github_token = "[REDACTED]" }),
+//! );
+//! assert_eq!(
+//!     render_diff(&policy.redact_changes(&diff(&old, &new))),
+//!     r#"~ name: "app" -> "app2"
+//! ~ github_token: "[REDACTED]" -> "[REDACTED]"
+//! "#,
+//! );
+//! ```
+
+use crate::{diff::*, path::*, shared::*, tree::*, typed::*};
+
+use std::{fmt, str::FromStr};
+
+/// The text that replaces secret values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A value that is redacted when formatted.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_exposed(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: FromStr> FromStr for Secret<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Secret)
+    }
+}
+
+impl<T: FromAtom> FromAtom for Secret<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+    const SECRET: bool = true;
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        T::from_atom(value).map(Secret)
+    }
+}
+
+/// Decides which atoms are secret.
+///
+/// An atom is secret if any key on its path matches one of `key_patterns`,
+/// or if its path (or the path of one of its ancestors) matches one of
+/// `path_patterns`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecretPolicy {
+    /// Globs matched against individual keys, ignoring ASCII case.
+    /// `*` matches any sequence of characters.
+    pub key_patterns: Vec<String>,
+    /// Globs matched against whole paths, written in [`Path`] syntax
+    /// (e.g., `database.password` or `servers[*].credentials`).
+    /// `*` matches any sequence of characters other than `.`, `[`, and `]`.
+    pub path_patterns: Vec<String>,
+}
+
+impl SecretPolicy {
+    /// A policy with common naming conventions for secret keys:
+    /// `*_token`, `*_secret`, `*password`, and `*api_key`.
+    pub fn conventional() -> Self {
+        Self {
+            key_patterns: ["*_token", "*_secret", "*password", "*api_key"]
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+            path_patterns: vec![],
+        }
+    }
+
+    pub fn with_key_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.key_patterns.push(pattern.into());
+        self
+    }
+
+    pub fn with_path_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.path_patterns.push(pattern.into());
+        self
+    }
+
+    /// Returns `true` if the node at `path` is secret
+    /// (or is inside a secret node).
+    pub fn is_secret(&self, path: &Path) -> bool {
+        let key_matches = path.segments.iter().any(|segment| match segment {
+            PathSegment::Key(key) => self
+                .key_patterns
+                .iter()
+                .any(|pattern| glob_matches(pattern, key, true)),
+            PathSegment::Index(_) => false,
+        });
+        key_matches
+            || self
+                .path_patterns
+                .iter()
+                .any(|pattern| path_pattern_matches(pattern, path))
+    }
+
+    /// Returns a copy of `map` with every secret atom redacted.
+    pub fn redact(&self, map: &Map) -> Map {
+        self.redact_node(&Path::root(), &Node::Map(map.clone()))
+            .map()
+            .expect("Redacting a map should return a map")
+    }
+
+    /// Returns a copy of `node`, which is located at `path`,
+    /// with every secret atom redacted.
+    pub fn redact_node(&self, path: &Path, node: &Node) -> Node {
+        if self.is_secret(path) {
+            return redact_all(node);
+        }
+        match node {
+            Node::Atom(_) => node.clone(),
+            Node::Map(map) => Node::Map(Map {
+                entries: map
+                    .entries
+                    .iter()
+                    .map(|entry| MapEntry {
+                        key: entry.key.clone(),
+                        value: self.redact_node(&path.with_key(entry.key.clone()), &entry.value),
+                    })
+                    .collect(),
+            }),
+            Node::List(list) => Node::List(List {
+                elements: list
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| self.redact_node(&path.with_index(i), element))
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Returns a copy of `changes` with every secret atom redacted,
+    /// suitable for [`render_diff`].
+    pub fn redact_changes(&self, changes: &[Change]) -> Vec<Change> {
+        changes
+            .iter()
+            .map(|change| self.redact_change(change))
+            .collect()
+    }
+
+    /// Returns a copy of `change` with every secret atom redacted.
+    pub fn redact_change(&self, change: &Change) -> Change {
+        change.redact_at(self, &Path::root())
+    }
+
+    /// Redacts the value of an [`AccessError::InvalidValue`]
+    /// if its path is secret.
+    pub fn redact_error(&self, err: AccessError) -> AccessError {
+        err.redact_at(self, &Path::root())
+    }
+}
+
+/// A value that can be formatted with its secret atoms redacted.
+pub trait Redact: Sized {
+    /// Returns a copy of `self`, which is located at `path`,
+    /// with every atom that `policy` marks secret redacted.
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self;
+
+    /// Returns a wrapper whose `Debug` output (and `Display` output,
+    /// if `Self` has one) redacts the atoms that `policy` marks secret.
+    ///
+    /// `self` is assumed to be located at the root;
+    /// use [`Redacted::with_path`] to format a subtree.
+    fn redacted<'a>(&'a self, policy: &'a SecretPolicy) -> Redacted<'a, Self> {
+        Redacted {
+            value: self,
+            policy,
+            path: Path::root(),
+        }
+    }
+}
+
+/// Formats a value with its secret atoms redacted;
+/// see [`Redact::redacted`].
+#[derive(Clone)]
+pub struct Redacted<'a, T> {
+    value: &'a T,
+    policy: &'a SecretPolicy,
+    path: Path,
+}
+
+impl<T> Redacted<'_, T> {
+    /// Sets the path at which the value is located,
+    /// which the policy's patterns are matched against.
+    /// For a [`MapEntry`], this is the path of the map containing it.
+    pub fn with_path(mut self, path: Path) -> Self {
+        self.path = path;
+        self
+    }
+}
+
+impl<T: Redact + fmt::Debug> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.value.redact_at(self.policy, &self.path), f)
+    }
+}
+
+impl<T: Redact + fmt::Display> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value.redact_at(self.policy, &self.path), f)
+    }
+}
+
+impl Redact for Node {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        policy.redact_node(path, self)
+    }
+}
+
+impl Redact for Map {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        policy
+            .redact_node(path, &Node::Map(self.clone()))
+            .map()
+            .expect("Redacting a map should return a map")
+    }
+}
+
+impl Redact for List {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        policy
+            .redact_node(path, &Node::List(self.clone()))
+            .list()
+            .expect("Redacting a list should return a list")
+    }
+}
+
+/// `path` is the path of the map containing the entry.
+impl Redact for MapEntry {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        MapEntry {
+            key: self.key.clone(),
+            value: policy.redact_node(&path.with_key(self.key.clone()), &self.value),
+        }
+    }
+}
+
+impl Redact for SharedNode {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        policy.redact_node(path, &Node::from(self)).into()
+    }
+}
+
+impl Redact for SharedMap {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        Map::from(self).redact_at(policy, path).into()
+    }
+}
+
+impl Redact for SharedList {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        List::from(self).redact_at(policy, path).into()
+    }
+}
+
+/// `path` is the path of the map containing the entry.
+impl Redact for SharedMapEntry {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        SharedMapEntry {
+            key: self.key.clone(),
+            value: self
+                .value
+                .redact_at(policy, &path.with_key(self.key.clone())),
+        }
+    }
+}
+
+/// The change's path is relative to `path`.
+impl Redact for Change {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        let path = path.join(&self.path);
+        let redact = |node: &Node| policy.redact_node(&path, node);
+        let kind = match &self.kind {
+            ChangeKind::Added(node) => ChangeKind::Added(redact(node)),
+            ChangeKind::Removed(node) => ChangeKind::Removed(redact(node)),
+            ChangeKind::Changed { old, new } => ChangeKind::Changed {
+                old: redact(old),
+                new: redact(new),
+            },
+        };
+        Change {
+            path: self.path.clone(),
+            kind,
+        }
+    }
+}
+
+/// The error's path is relative to `path`.
+impl Redact for AccessError {
+    fn redact_at(&self, policy: &SecretPolicy, path: &Path) -> Self {
+        match self {
+            AccessError::InvalidValue {
+                path: value_path,
+                expected,
+                reason,
+                ..
+            } if policy.is_secret(&path.join(value_path)) => AccessError::InvalidValue {
+                path: value_path.clone(),
+                value: REDACTED.to_owned(),
+                expected,
+                reason: reason.clone(),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+fn redact_all(node: &Node) -> Node {
+    match node {
+        Node::Atom(_) => Node::Atom(Atom {
+            value: REDACTED.to_owned(),
+        }),
+        Node::Map(map) => Node::Map(Map {
+            entries: map
+                .entries
+                .iter()
+                .map(|entry| MapEntry {
+                    key: entry.key.clone(),
+                    value: redact_all(&entry.value),
+                })
+                .collect(),
+        }),
+        Node::List(list) => Node::List(List {
+            elements: list.elements.iter().map(redact_all).collect(),
+        }),
+    }
+}
+
+/// Returns `true` if `pattern` (see [`SecretPolicy::path_patterns`])
+/// matches `path` or one of its ancestors (other than the root).
+fn path_pattern_matches(pattern: &str, path: &Path) -> bool {
+    let mut segments = path.segments.iter();
+    let mut is_empty = true;
+    for segment_pattern in SegmentPatterns::new(pattern) {
+        let Some(segment) = segments.next() else {
+            return false;
+        };
+        let matches = match (segment_pattern, segment) {
+            (SegmentPattern::Key(pattern), PathSegment::Key(key)) => {
+                glob_matches(pattern, key, false)
+            }
+            (SegmentPattern::Index(pattern), PathSegment::Index(index)) => {
+                glob_matches(pattern, &index.to_string(), false)
+            }
+            _ => false,
+        };
+        if !matches {
+            return false;
+        }
+        is_empty = false;
+    }
+    !is_empty
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SegmentPattern<'a> {
+    Key(&'a str),
+    Index(&'a str),
+    /// A malformed segment, which matches nothing.
+    Invalid,
+}
+
+/// Splits a path pattern into the patterns of its segments.
+struct SegmentPatterns<'a> {
+    rest: &'a str,
+    is_first: bool,
+}
+
+impl<'a> SegmentPatterns<'a> {
+    fn new(pattern: &'a str) -> Self {
+        Self {
+            rest: pattern,
+            is_first: true,
+        }
+    }
+}
+
+impl<'a> Iterator for SegmentPatterns<'a> {
+    type Item = SegmentPattern<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let is_first = std::mem::replace(&mut self.is_first, false);
+        if let Some(rest) = self.rest.strip_prefix('[') {
+            let Some((index, rest)) = rest.split_once(']') else {
+                self.rest = "";
+                return Some(SegmentPattern::Invalid);
+            };
+            self.rest = rest;
+            return Some(SegmentPattern::Index(index));
+        }
+        let rest = if is_first {
+            self.rest
+        } else if let Some(rest) = self.rest.strip_prefix('.') {
+            rest
+        } else {
+            self.rest = "";
+            return Some(SegmentPattern::Invalid);
+        };
+        let end = rest.find(['.', '[']).unwrap_or(rest.len());
+        self.rest = &rest[end..];
+        Some(SegmentPattern::Key(&rest[..end]))
+    }
+}
+
+/// `*` matches any (possibly empty) sequence of chars.
+/// All other pattern chars match themselves.
+fn glob_matches(pattern: &str, text: &str, ignore_ascii_case: bool) -> bool {
+    // Bytes can be compared instead of chars,
+    // since a pattern char never matches part of a text char.
+    let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+    let equal = |p: u8, c: u8| {
+        if ignore_ascii_case {
+            p.eq_ignore_ascii_case(&c)
+        } else {
+            p == c
+        }
+    };
+    let (mut p, mut t) = (0, 0);
+    // The pattern position after the last `*`,
+    // and the text position where its match ends.
+    let mut backtrack = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            p += 1;
+            backtrack = Some((p, t));
+        } else if p < pattern.len() && equal(pattern[p], text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` match one more byte.
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
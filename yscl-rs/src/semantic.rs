@@ -0,0 +1,232 @@
+//! Equality, hashing, and ordering that ignore map entry order.
+//!
+//! YSCL maps are keyed, so `{ a = "1" b = "2" }` and `{ b = "2" a = "1" }`
+//! mean the same thing, even though the derived `PartialEq` and `Hash`
+//! of [`Map`] consider them different.
+//! List order is always significant.
+//!
+//! These functions assume every map's keys are unique,
+//! which holds for any map produced by the parser or the editing methods.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//! use std::collections::HashSet;
+//!
+//! let a = yscl_node!({ a = "1", b = ["x", "y"] });
+//! let b = yscl_node!({ b = ["x", "y"], a = "1" });
+//! let c = yscl_node!({ a = "1", b = ["y", "x"] });
+//!
+//! assert_ne!(a, b);
+//! assert!(a.semantic_eq(&b));
+//! assert!(!a.semantic_eq(&c));
+//!
+//! let set: HashSet<_> = [a, b, c].into_iter().map(Semantic).collect();
+//! assert_eq!(set.len(), 2);
+//! ```
+//!
+//! The semantic order is the derived order of [`Node`]
+//! (atoms, then maps, then lists),
+//! except that maps are compared as if their entries were sorted by key.
+
+use crate::tree::*;
+
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+/// A wrapper whose `PartialEq`, `Eq`, `Hash`, `PartialOrd`, and `Ord`
+/// ignore map entry order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Semantic<T>(pub T);
+
+impl NodeRef<'_> {
+    pub fn semantic_eq(self, other: NodeRef) -> bool {
+        match (self, other) {
+            (NodeRef::Atom(a), NodeRef::Atom(b)) => a == b,
+            (NodeRef::Map(a), NodeRef::Map(b)) => a.semantic_eq(b),
+            (NodeRef::List(a), NodeRef::List(b)) => a.semantic_eq(b),
+            _ => false,
+        }
+    }
+
+    /// Consistent with [`NodeRef::semantic_eq`]:
+    /// returns `Equal` exactly when the nodes are semantically equal.
+    pub fn semantic_cmp(self, other: NodeRef) -> Ordering {
+        match (self, other) {
+            (NodeRef::Atom(a), NodeRef::Atom(b)) => a.cmp(b),
+            (NodeRef::Map(a), NodeRef::Map(b)) => a.semantic_cmp(b),
+            (NodeRef::List(a), NodeRef::List(b)) => a.semantic_cmp(b),
+            _ => kind_rank(self).cmp(&kind_rank(other)),
+        }
+    }
+
+    /// Consistent with [`NodeRef::semantic_eq`]:
+    /// semantically equal nodes feed `state` the same data.
+    pub fn semantic_hash<H: Hasher>(self, state: &mut H) {
+        match self {
+            NodeRef::Atom(atom) => {
+                0u8.hash(state);
+                atom.hash(state);
+            }
+            NodeRef::Map(map) => {
+                1u8.hash(state);
+                map.semantic_hash(state);
+            }
+            NodeRef::List(list) => {
+                2u8.hash(state);
+                list.semantic_hash(state);
+            }
+        }
+    }
+}
+
+fn kind_rank(node: NodeRef) -> u8 {
+    match node {
+        NodeRef::Atom(_) => 0,
+        NodeRef::Map(_) => 1,
+        NodeRef::List(_) => 2,
+    }
+}
+
+impl Node {
+    pub fn semantic_eq(&self, other: &Node) -> bool {
+        self.as_ref().semantic_eq(other.as_ref())
+    }
+
+    pub fn semantic_cmp(&self, other: &Node) -> Ordering {
+        self.as_ref().semantic_cmp(other.as_ref())
+    }
+
+    pub fn semantic_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().semantic_hash(state)
+    }
+}
+
+impl Map {
+    pub fn semantic_eq(&self, other: &Map) -> bool {
+        self.len() == other.len()
+            && sorted_entries(self)
+                .into_iter()
+                .zip(sorted_entries(other))
+                .all(|(a, b)| a.key == b.key && a.value.semantic_eq(&b.value))
+    }
+
+    /// Compares the entries, sorted by key, lexicographically.
+    pub fn semantic_cmp(&self, other: &Map) -> Ordering {
+        sorted_entries(self)
+            .into_iter()
+            .map(|entry| (&entry.key, Semantic(entry.value.as_ref())))
+            .cmp(
+                sorted_entries(other)
+                    .into_iter()
+                    .map(|entry| (&entry.key, Semantic(entry.value.as_ref()))),
+            )
+    }
+
+    pub fn semantic_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for entry in sorted_entries(self) {
+            entry.key.hash(state);
+            entry.value.semantic_hash(state);
+        }
+    }
+}
+
+impl List {
+    pub fn semantic_eq(&self, other: &List) -> bool {
+        self.len() == other.len()
+            && self
+                .elements
+                .iter()
+                .zip(&other.elements)
+                .all(|(a, b)| a.semantic_eq(b))
+    }
+
+    /// Compares the elements lexicographically.
+    pub fn semantic_cmp(&self, other: &List) -> Ordering {
+        self.elements
+            .iter()
+            .map(|element| Semantic(element.as_ref()))
+            .cmp(
+                other
+                    .elements
+                    .iter()
+                    .map(|element| Semantic(element.as_ref())),
+            )
+    }
+
+    pub fn semantic_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for element in &self.elements {
+            element.semantic_hash(state);
+        }
+    }
+}
+
+fn sorted_entries(map: &Map) -> Vec<&MapEntry> {
+    let mut entries: Vec<&MapEntry> = map.entries.iter().collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+macro_rules! impl_semantic {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq for Semantic<$t> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0.semantic_eq(&other.0)
+                }
+            }
+
+            impl Eq for Semantic<$t> {}
+
+            impl Hash for Semantic<$t> {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.0.semantic_hash(state)
+                }
+            }
+
+            impl PartialOrd for Semantic<$t> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for Semantic<$t> {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.semantic_cmp(&other.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_semantic!(Node, Map, List);
+
+impl PartialEq for Semantic<NodeRef<'_>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.semantic_eq(other.0)
+    }
+}
+
+impl Eq for Semantic<NodeRef<'_>> {}
+
+impl Hash for Semantic<NodeRef<'_>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.semantic_hash(state)
+    }
+}
+
+impl PartialOrd for Semantic<NodeRef<'_>> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Semantic<NodeRef<'_>> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.semantic_cmp(other.0)
+    }
+}
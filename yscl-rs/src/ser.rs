@@ -0,0 +1,629 @@
+//! Serializing Rust types as YSCL with [serde](https://serde.rs).
+//!
+//! Requires the `serde` feature.
+//! This is the inverse of [`de`](crate::de), so a value written with
+//! [`to_string`] can be read back with [`from_str`](crate::de::from_str),
+//! with one exception: in internally tagged and untagged enums,
+//! a string whose text looks like a number or `bool` (e.g., `"80"`)
+//! is read back as that number or `bool` (see [`de`](crate::de)).
+//!
+//! - Structs and maps are written as maps, and sequences and tuples as lists.
+//! - Every scalar (numbers, `bool`s, `char`s, and strings) is written as an atom.
+//! - `()` and unit structs are written as empty atoms (`""`).
+//! - `None` entries are omitted from maps.
+//!   `None` cannot be written at the top level or in a list.
+//! - A unit enum variant is written as an atom containing the variant's name.
+//!   Any other variant is written as a map with one entry,
+//!   whose key is the variant's name.
+//!
+//! Map keys (including struct field names) must be strings that are
+//! valid identifiers, and the top-level value must be a map.
+//! Errors report the [`Path`] of the value that could not be written.
+//!
+//! The prelude does not include [`to_string`], since it would conflict with
+//! [`write::to_string`](crate::write::to_string). Use `yscl::to_string`.
+//!
+//! ## Example
+//! ```rust
+//! use serde::Serialize;
+//! use yscl::prelude::*;
+//!
+//! #[derive(Serialize)]
+//! struct Server {
+//!     port: u16,
+//!     verbose: bool,
+//!     hosts: Vec<String>,
+//!     timeout_secs: Option<u64>,
+//! }
+//!
+//! let server = Server {
+//!     port: 8080,
+//!     verbose: true,
+//!     hosts: vec!["127.0.0.1".to_owned()],
+//!     timeout_secs: None,
+//! };
+//! assert_eq!(
+//!     yscl::to_string(&server).unwrap(),
+//!     r#"port = "8080"
+//! verbose = "true"
+//! hosts = [
+//!     "127.0.0.1"
+//! ]
+//! "#,
+//! );
+//!
+//! let err = yscl::to_string(&vec![1, 2]).unwrap_err();
+//! assert_eq!(err.to_string(), "expected a map at the top level, found list");
+//! ```
+
+use crate::{path::*, tree::*, write::*};
+
+use serde::ser::{self, Impossible, Serialize};
+
+use std::fmt;
+
+/// The value at `path` cannot be written as YSCL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializeError {
+    pub path: Path,
+    pub message: String,
+}
+
+impl SerializeError {
+    /// Prepends `segment` to the error's path.
+    fn under(mut self, segment: PathSegment) -> Self {
+        self.path.segments.insert(0, segment);
+        self
+    }
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_root() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerializeError {
+            path: Path::root(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Serializes `value` as a YSCL document, using the default [`WriteOptions`].
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, SerializeError> {
+    to_string_with(value, &WriteOptions::default())
+}
+
+pub fn to_string_with<T: ?Sized + Serialize>(
+    value: &T,
+    options: &WriteOptions,
+) -> Result<String, SerializeError> {
+    Ok(crate::write::to_string_with(&to_map(value)?, options))
+}
+
+/// Serializes `value` as a document (i.e., a map).
+pub fn to_map<T: ?Sized + Serialize>(value: &T) -> Result<Map, SerializeError> {
+    match to_node(value)? {
+        Node::Map(map) => Ok(map),
+        other => Err(ser::Error::custom(format!(
+            "expected a map at the top level, found {}",
+            other.kind()
+        ))),
+    }
+}
+
+/// Serializes `value` as a node.
+pub fn to_node<T: ?Sized + Serialize>(value: &T) -> Result<Node, SerializeError> {
+    value
+        .serialize(NodeSerializer)?
+        .ok_or_else(|| ser::Error::custom("cannot write None at the top level"))
+}
+
+/// Serializes a value as a node,
+/// or as `None` if the value should be omitted from its map.
+struct NodeSerializer;
+
+fn atom(value: impl ToString) -> Result<Option<Node>, SerializeError> {
+    Ok(Some(Node::Atom(Atom {
+        value: value.to_string(),
+    })))
+}
+
+fn single_entry_map(variant: &'static str, value: Node) -> Result<Option<Node>, SerializeError> {
+    let key = identifier(variant)?;
+    Ok(Some(Node::Map(Map {
+        entries: vec![MapEntry { key, value }],
+    })))
+}
+
+fn identifier(key: &str) -> Result<Identifier, SerializeError> {
+    match Identifier::new(key.to_owned()) {
+        Ok(key) => Ok(key),
+        Err(_) => Err(ser::Error::custom(format!(
+            "invalid key {:?} (keys must be identifiers)",
+            key
+        ))),
+    }
+}
+
+macro_rules! serialize_as_atom {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+                atom(value)
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for NodeSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = TupleVariantSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = StructVariantSerializer;
+
+    serialize_as_atom! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        match std::str::from_utf8(value) {
+            Ok(value) => atom(value),
+            Err(_) => Err(ser::Error::custom("bytes must be valid UTF-8")),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        atom("")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        atom("")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        atom(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let under_variant = |err: SerializeError| match identifier(variant) {
+            Ok(key) => err.under(PathSegment::Key(key)),
+            Err(_) => err,
+        };
+        let Some(value) = value.serialize(NodeSerializer).map_err(under_variant)? else {
+            return Err(under_variant(ser::Error::custom(
+                "cannot write None as an enum variant's value",
+            )));
+        };
+        single_entry_map(variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ListSerializer {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(TupleVariantSerializer {
+            variant,
+            list: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapSerializer {
+            map: Map::default(),
+            pending_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(StructVariantSerializer {
+            variant,
+            map: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct ListSerializer {
+    elements: Vec<Node>,
+}
+
+impl ListSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerializeError> {
+        let segment = PathSegment::Index(self.elements.len());
+        match value.serialize(NodeSerializer) {
+            Ok(Some(element)) => {
+                self.elements.push(element);
+                Ok(())
+            }
+            Ok(None) => Err(SerializeError::under(
+                ser::Error::custom("cannot write None in a list"),
+                segment,
+            )),
+            Err(err) => Err(err.under(segment)),
+        }
+    }
+
+    fn finish(self) -> Node {
+        Node::List(List {
+            elements: self.elements,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+struct TupleVariantSerializer {
+    variant: &'static str,
+    list: ListSerializer,
+}
+
+impl ser::SerializeTupleVariant for TupleVariantSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let variant = identifier(self.variant)?;
+        self.list
+            .push(value)
+            .map_err(|err| err.under(PathSegment::Key(variant)))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        single_entry_map(self.variant, self.list.finish())
+    }
+}
+
+struct MapSerializer {
+    map: Map,
+    pending_key: Option<Identifier>,
+}
+
+impl MapSerializer {
+    fn insert<T: ?Sized + Serialize>(
+        &mut self,
+        key: Identifier,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        if self.map.entries.iter().any(|entry| entry.key == key) {
+            return Err(ser::Error::custom(format!("duplicate key {:?}", &*key)));
+        }
+        match value.serialize(NodeSerializer) {
+            Ok(Some(value)) => {
+                self.map.entries.push(MapEntry { key, value });
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(err) => Err(err.under(PathSegment::Key(key))),
+        }
+    }
+
+    fn finish(self) -> Node {
+        Node::Map(self.map)
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.pending_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .pending_key
+            .take()
+            .expect("serialize_value should be called after serialize_key");
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.insert(identifier(key)?, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(self.finish()))
+    }
+}
+
+struct StructVariantSerializer {
+    variant: &'static str,
+    map: MapSerializer,
+}
+
+impl ser::SerializeStructVariant for StructVariantSerializer {
+    type Ok = Option<Node>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        let variant = identifier(self.variant)?;
+        ser::SerializeStruct::serialize_field(&mut self.map, key, value)
+            .map_err(|err| err.under(PathSegment::Key(variant)))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        single_entry_map(self.variant, self.map.finish())
+    }
+}
+
+/// Serializes a map key, which must be a string that is a valid identifier.
+struct KeySerializer;
+
+fn key_must_be_string<T>() -> Result<T, SerializeError> {
+    Err(ser::Error::custom("map keys must be strings"))
+}
+
+macro_rules! reject_key {
+    ($($method:ident($($ty:ty),*),)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<Self::Ok, Self::Error> {
+                key_must_be_string()
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = Identifier;
+    type Error = SerializeError;
+    type SerializeSeq = Impossible<Identifier, SerializeError>;
+    type SerializeTuple = Impossible<Identifier, SerializeError>;
+    type SerializeTupleStruct = Impossible<Identifier, SerializeError>;
+    type SerializeTupleVariant = Impossible<Identifier, SerializeError>;
+    type SerializeMap = Impossible<Identifier, SerializeError>;
+    type SerializeStruct = Impossible<Identifier, SerializeError>;
+    type SerializeStructVariant = Impossible<Identifier, SerializeError>;
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
+        identifier(value)
+    }
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
+        identifier(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        identifier(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    reject_key! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        key_must_be_string()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        key_must_be_string()
+    }
+}
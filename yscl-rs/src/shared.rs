@@ -0,0 +1,354 @@
+//! An immutable, structurally shared variant of the [`tree`](crate::tree) types.
+//!
+//! Every map, list, and atom is stored behind an [`Arc`],
+//! so cloning a [`SharedNode`] (or [`SharedMap`] or [`SharedList`]) is O(1),
+//! and clones can be sent to other threads.
+//!
+//! Editing uses copy-on-write: the mutating methods only copy a map or list
+//! if it is shared with another clone, and [`SharedNode::resolve_mut`]
+//! copies only the maps and lists on the path from the root to the target.
+//! Everything else stays shared between the old and new versions.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let old = SharedNode::from(yscl_node!({
+//!     server = { port = "80" },
+//!     plugins = ["auth", "metrics"]
+//! }));
+//!
+//! let mut new = old.clone();
+//! *new.resolve_mut(&"server.port".parse().unwrap()).unwrap() = yscl_node!("8080").into();
+//!
+//! assert_eq!(Node::from(&old), yscl_node!({ server = { port = "80" }, plugins = ["auth", "metrics"] }));
+//! assert_eq!(Node::from(&new), yscl_node!({ server = { port = "8080" }, plugins = ["auth", "metrics"] }));
+//!
+//! // The untouched subtree is shared, not copied.
+//! let plugins = |node: &SharedNode| node.as_map().unwrap().get("plugins").unwrap().as_list().unwrap().clone();
+//! assert!(plugins(&old).ptr_eq(&plugins(&new)));
+//! ```
+
+use crate::{path::*, tree::*};
+
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SharedNode {
+    Atom(Arc<Atom>),
+    Map(SharedMap),
+    List(SharedList),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedMap {
+    entries: Arc<Vec<SharedMapEntry>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedMapEntry {
+    pub key: Identifier,
+    pub value: SharedNode,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedList {
+    elements: Arc<Vec<SharedNode>>,
+}
+
+impl SharedNode {
+    pub fn as_atom(&self) -> Option<&Atom> {
+        match self {
+            SharedNode::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&SharedMap> {
+        match self {
+            SharedNode::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&SharedList> {
+        match self {
+            SharedNode::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_map_mut(&mut self) -> Option<&mut SharedMap> {
+        match self {
+            SharedNode::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Option<&mut SharedList> {
+        match self {
+            SharedNode::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// Returns the node at `path` (relative to `self`), if it exists.
+    pub fn resolve(&self, path: &Path) -> Option<&SharedNode> {
+        let mut node = self;
+        for segment in &path.segments {
+            node = match (node, segment) {
+                (SharedNode::Map(map), PathSegment::Key(key)) => map.get(key)?,
+                (SharedNode::List(list), PathSegment::Index(index)) => list.get(*index)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Returns a mutable reference to the node at `path`, if it exists.
+    ///
+    /// Every map and list on the way to the node that is shared with
+    /// another clone is copied (shallowly) first, so other clones are
+    /// never affected.
+    pub fn resolve_mut(&mut self, path: &Path) -> Option<&mut SharedNode> {
+        // Check first, so a missing path doesn't copy anything.
+        self.resolve(path)?;
+
+        let mut node = self;
+        for segment in &path.segments {
+            node = match (node, segment) {
+                (SharedNode::Map(map), PathSegment::Key(key)) => map.get_mut(key)?,
+                (SharedNode::List(list), PathSegment::Index(index)) => list.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+}
+
+impl SharedMap {
+    pub fn get<K>(&self, key: &K) -> Option<&SharedNode>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.entries
+            .iter()
+            .find(|entry| *entry.key == *key.as_ref())
+            .map(|entry| &entry.value)
+    }
+
+    /// Copies the map's entries first if they are shared.
+    pub fn get_mut<K>(&mut self, key: &K) -> Option<&mut SharedNode>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = self.position(key)?;
+        Some(&mut Arc::make_mut(&mut self.entries)[index].value)
+    }
+
+    pub fn contains_key<K>(&self, key: &K) -> bool
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.position(key).is_some()
+    }
+
+    fn position<K>(&self, key: &K) -> Option<usize>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.entries
+            .iter()
+            .position(|entry| *entry.key == *key.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[SharedMapEntry] {
+        &self.entries
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Identifier, &SharedNode)> {
+        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Like [`Map::insert`].
+    /// Copies the map's entries first if they are shared.
+    pub fn insert(&mut self, key: Identifier, value: SharedNode) -> Option<SharedNode> {
+        let entries = Arc::make_mut(&mut self.entries);
+        match entries.iter_mut().find(|entry| entry.key == key) {
+            Some(entry) => Some(std::mem::replace(&mut entry.value, value)),
+            None => {
+                entries.push(SharedMapEntry { key, value });
+                None
+            }
+        }
+    }
+
+    /// Like [`Map::remove`].
+    /// Copies the map's entries first if they are shared.
+    pub fn remove<K>(&mut self, key: &K) -> Option<SharedNode>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = self.position(key)?;
+        Some(Arc::make_mut(&mut self.entries).remove(index).value)
+    }
+
+    /// Returns `true` if both maps share the same storage,
+    /// which implies they are equal.
+    pub fn ptr_eq(&self, other: &SharedMap) -> bool {
+        Arc::ptr_eq(&self.entries, &other.entries)
+    }
+}
+
+impl SharedList {
+    pub fn get(&self, index: usize) -> Option<&SharedNode> {
+        self.elements.get(index)
+    }
+
+    /// Copies the list's elements first if they are shared.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut SharedNode> {
+        if index >= self.elements.len() {
+            return None;
+        }
+        Some(&mut Arc::make_mut(&mut self.elements)[index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn elements(&self) -> &[SharedNode] {
+        &self.elements
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SharedNode> {
+        self.elements.iter()
+    }
+
+    /// Copies the list's elements first if they are shared.
+    pub fn push(&mut self, value: SharedNode) {
+        Arc::make_mut(&mut self.elements).push(value);
+    }
+
+    /// Like [`List::insert`].
+    /// Copies the list's elements first if they are shared.
+    pub fn insert(&mut self, index: usize, value: SharedNode) -> Result<(), EditError> {
+        if index > self.len() {
+            return Err(EditError::IndexOutOfBounds(index, self.len()));
+        }
+        Arc::make_mut(&mut self.elements).insert(index, value);
+        Ok(())
+    }
+
+    /// Like [`List::remove`].
+    /// Copies the list's elements first if they are shared.
+    pub fn remove(&mut self, index: usize) -> Result<SharedNode, EditError> {
+        if index >= self.len() {
+            return Err(EditError::IndexOutOfBounds(index, self.len()));
+        }
+        Ok(Arc::make_mut(&mut self.elements).remove(index))
+    }
+
+    /// Returns `true` if both lists share the same storage,
+    /// which implies they are equal.
+    pub fn ptr_eq(&self, other: &SharedList) -> bool {
+        Arc::ptr_eq(&self.elements, &other.elements)
+    }
+}
+
+impl From<Node> for SharedNode {
+    fn from(node: Node) -> Self {
+        match node {
+            Node::Atom(atom) => SharedNode::Atom(Arc::new(atom)),
+            Node::Map(map) => SharedNode::Map(map.into()),
+            Node::List(list) => SharedNode::List(list.into()),
+        }
+    }
+}
+
+impl From<Map> for SharedMap {
+    fn from(map: Map) -> Self {
+        let entries = map
+            .entries
+            .into_iter()
+            .map(|entry| SharedMapEntry {
+                key: entry.key,
+                value: entry.value.into(),
+            })
+            .collect();
+        SharedMap {
+            entries: Arc::new(entries),
+        }
+    }
+}
+
+impl From<List> for SharedList {
+    fn from(list: List) -> Self {
+        SharedList {
+            elements: Arc::new(list.elements.into_iter().map(SharedNode::from).collect()),
+        }
+    }
+}
+
+impl From<&SharedNode> for Node {
+    fn from(node: &SharedNode) -> Self {
+        match node {
+            SharedNode::Atom(atom) => Node::Atom(Atom::clone(atom)),
+            SharedNode::Map(map) => Node::Map(map.into()),
+            SharedNode::List(list) => Node::List(list.into()),
+        }
+    }
+}
+
+impl From<&SharedMap> for Map {
+    fn from(map: &SharedMap) -> Self {
+        Map {
+            entries: map
+                .entries
+                .iter()
+                .map(|entry| MapEntry {
+                    key: entry.key.clone(),
+                    value: (&entry.value).into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&SharedList> for List {
+    fn from(list: &SharedList) -> Self {
+        List {
+            elements: list.elements.iter().map(Node::from).collect(),
+        }
+    }
+}
+
+impl From<SharedNode> for Node {
+    fn from(node: SharedNode) -> Self {
+        Node::from(&node)
+    }
+}
+
+impl From<SharedMap> for Map {
+    fn from(map: SharedMap) -> Self {
+        Map::from(&map)
+    }
+}
+
+impl From<SharedList> for List {
+    fn from(list: SharedList) -> Self {
+        List::from(&list)
+    }
+}
@@ -74,7 +74,7 @@ fn assert_node_eq(expected: &Node, actual: &Node) -> Pass {
 
 fn get_commonality(left: &Node, right: &Node) -> String {
     let mut out = "".to_string();
-    std::mem::drop(write_commonality(&mut out, left, right, 0));
+    let _ = write_commonality(&mut out, left, right, 0);
     out
 }
 
@@ -89,15 +89,11 @@ fn write_commonality(
     let i1 = " ".repeat(next_indent_level);
 
     match (left, right) {
-        (Node::Atom(left), Node::Atom(right)) => {
-            if left.value == right.value {
-                out.push_str(&format!("{:?}", left.value));
-            } else {
-                return Err(());
-            }
+        (Node::Atom(left), Node::Atom(right)) if left.value == right.value => {
+            out.push_str(&format!("{:?}", left.value));
         }
         (Node::Map(left), Node::Map(right)) => {
-            out.push_str("{");
+            out.push('{');
             let mut left_entries = left.entries.iter();
             let mut right_entries = right.entries.iter();
             loop {
@@ -121,7 +117,7 @@ fn write_commonality(
             out.push_str(&format!("\n{}}}\n", i0));
         }
         (Node::List(left), Node::List(right)) => {
-            out.push_str("[");
+            out.push('[');
             let mut left_elements = left.elements.iter();
             let mut right_elements = right.elements.iter();
             loop {
@@ -0,0 +1,139 @@
+use crate::prelude::*;
+
+fn sample() -> Map {
+    yscl_node!({
+        c = { d = "" },
+        b = ["x\"\\\n\t", {}, []],
+        a = "1"
+    })
+    .map()
+    .unwrap()
+}
+
+const SAMPLE_CANONICAL: &str = r#"a = "1"
+b = [
+    "x\"\\\n\u000009"
+    {}
+    []
+]
+c = {
+    d = ""
+}
+"#;
+
+#[test]
+fn canonicalize_sorts_keys_recursively() {
+    let map = yscl_node!({ b = { z = "1", y = "2" }, a = [{ q = "", p = "" }] })
+        .map()
+        .unwrap();
+    assert_eq!(
+        yscl_node!({ a = [{ p = "", q = "" }], b = { y = "2", z = "1" } }),
+        Node::Map(canonicalize(&map))
+    );
+}
+
+#[test]
+fn canonical_string_is_fixed_and_parses_back() {
+    let text = canonical_string(&sample());
+    assert_eq!(SAMPLE_CANONICAL, text);
+    assert_eq!(canonicalize(&sample()), parse_doc(&text).unwrap());
+    assert_eq!("", canonical_string(&Map::default()));
+}
+
+#[test]
+fn content_hash_is_stable() {
+    assert_eq!(
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        content_hash(&Map::default()).to_string()
+    );
+    assert_eq!(
+        "5100cc7ed9995e9b4d7afefc3d70231e3900e67898c35f15db501ac363ea610c",
+        content_hash(&sample()).to_string()
+    );
+
+    // Spans more than one SHA-256 block.
+    let long = yscl_node!({ k = "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx" })
+        .map()
+        .unwrap();
+    assert_eq!(
+        "bb32d2a60520a637ae12c510060b89e322d9f75aa91595e5b78117134cc9a7cd",
+        content_hash(&long).to_string()
+    );
+}
+
+/// The canonical text pins its own escaping, so changes to the
+/// writer's defaults (e.g., its escape mode) must not change these hashes.
+#[test]
+fn content_hash_is_independent_of_writer_escaping() {
+    let map = yscl_node!({
+        a = "\u{2028}",
+        b = "\u{2029}",
+        c = "\u{feff}",
+        d = "\u{1}"
+    })
+    .map()
+    .unwrap();
+    assert_eq!(
+        "a = \"\u{2028}\"\nb = \"\u{2029}\"\nc = \"\u{feff}\"\nd = \"\\u000001\"\n",
+        canonical_string(&map)
+    );
+    assert_eq!(
+        "80152c302c3f3cd631bf834997e0ddcbb74ff057d245e852db74b2748a89d60d",
+        content_hash(&map).to_string()
+    );
+    assert_eq!(
+        canonicalize(&map),
+        parse_doc(&canonical_string(&map)).unwrap()
+    );
+}
+
+#[test]
+fn content_hash_ignores_order_but_not_content() {
+    let a = yscl_node!({ x = "1", y = ["a", "b"] }).map().unwrap();
+    let b = yscl_node!({ y = ["a", "b"], x = "1" }).map().unwrap();
+    let c = yscl_node!({ x = "1", y = ["b", "a"] }).map().unwrap();
+    assert_eq!(content_hash(&a), content_hash(&b));
+    assert_ne!(content_hash(&a), content_hash(&c));
+}
+
+#[test]
+fn sha256_matches_nist_vectors() {
+    use crate::canonical::sha256::sha256;
+
+    let hex = |bytes: &[u8]| ContentHash(sha256(bytes)).to_string();
+    assert_eq!(
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        hex(b"")
+    );
+    assert_eq!(
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        hex(b"abc")
+    );
+    // 448 bits: the length no longer fits in the first block's padding.
+    assert_eq!(
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")
+    );
+    assert_eq!(
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+        hex(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu")
+    );
+
+    // Around the padding boundary.
+    assert_eq!(
+        "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318",
+        hex(&[b'a'; 55])
+    );
+    assert_eq!(
+        "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a",
+        hex(&[b'a'; 56])
+    );
+    assert_eq!(
+        "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb",
+        hex(&[b'a'; 64])
+    );
+    assert_eq!(
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+        hex(&vec![b'a'; 1_000_000])
+    );
+}
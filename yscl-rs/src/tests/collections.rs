@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+
+fn id(s: &str) -> Identifier {
+    Identifier::try_from(s).unwrap()
+}
+
+#[test]
+fn map_iterators_preserve_order() {
+    let mut map = yscl_node!({ b = "1", a = "2", c = "3" }).map().unwrap();
+    let keys: Vec<&str> = map.keys().map(|key| &**key).collect();
+    assert_eq!(vec!["b", "a", "c"], keys);
+
+    for (_, value) in &mut map {
+        if let Node::Atom(atom) = value {
+            atom.value.push('!');
+        }
+    }
+    let pairs: Vec<(String, Node)> = map
+        .clone()
+        .into_iter()
+        .map(|(key, value)| (key.into(), value))
+        .collect();
+    assert_eq!(
+        vec![
+            ("b".to_owned(), Node::from("1!")),
+            ("a".to_owned(), Node::from("2!")),
+            ("c".to_owned(), Node::from("3!")),
+        ],
+        pairs
+    );
+    assert_eq!(3, map.iter().len());
+    assert_eq!(Some((&id("c"), &Node::from("3!"))), map.iter().next_back());
+}
+
+#[test]
+fn map_from_iter_and_extend() {
+    let mut map: Map = [(id("a"), "1"), (id("b"), "2"), (id("a"), "3")]
+        .into_iter()
+        .collect();
+    assert_eq!(yscl_node!({ a = "3", b = "2" }), Node::Map(map.clone()));
+
+    map.extend([(id("c"), Node::from(vec![Node::from("x")]))]);
+    assert_eq!(yscl_node!({ a = "3", b = "2", c = ["x"] }), Node::Map(map));
+}
+
+#[test]
+fn list_traits() {
+    let mut list: List = ["a", "b"].into_iter().collect();
+    list.extend(vec!["c".to_owned()]);
+    list[0] = Node::from("z");
+    assert_eq!(Node::from("b"), list[1]);
+    let values: Vec<&str> = list
+        .iter()
+        .map(|node| node.as_ref().atom().unwrap().value.as_str())
+        .collect();
+    assert_eq!(vec!["z", "b", "c"], values);
+    assert_eq!(3, (&list).into_iter().count());
+    assert_eq!(yscl_node!(["z", "b", "c"]), Node::List(list));
+}
+
+#[test]
+fn index_map_by_key() {
+    let mut map = yscl_node!({ a = { b = "1" } }).map().unwrap();
+    assert_eq!(yscl_node!({ b = "1" }), map["a"]);
+    map["a"] = Node::from("2");
+    assert_eq!(Node::from("2"), map[&id("a")]);
+}
+
+#[test]
+#[should_panic(expected = "Key \"missing\" does not exist")]
+fn index_map_by_missing_key_panics() {
+    let map = Map::default();
+    let _ = &map["missing"];
+}
+
+#[test]
+fn try_from_pairs() {
+    assert_eq!(
+        Ok(yscl_node!({ a = "1", b = [] }).map().unwrap()),
+        Map::try_from(vec![
+            ("a".to_owned(), Node::from("1")),
+            ("b".to_owned(), Node::from(vec![])),
+        ])
+    );
+    assert_eq!(
+        Err(EditError::DuplicateKey(id("a"))),
+        Map::try_from(vec![
+            ("a".to_owned(), Node::from("1")),
+            ("a".to_owned(), Node::from("2")),
+        ])
+    );
+    assert_eq!(
+        Err(EditError::InvalidKey("0a".to_owned(), 0)),
+        Map::try_from(vec![("0a".to_owned(), Node::from("1"))])
+    );
+    assert_eq!(
+        Err(EditError::InvalidKey(String::new(), 0)),
+        Map::try_from(vec![(String::new(), Node::from("1"))])
+    );
+    assert_eq!(Err(0), Identifier::new(String::new()));
+    assert_eq!(Err(0), Identifier::try_from(""));
+}
+
+#[test]
+fn std_map_conversions() {
+    let map = yscl_node!({ b = "1", a = "2" }).map().unwrap();
+
+    let btree_map = BTreeMap::from(map.clone());
+    assert_eq!(Some(&Node::from("2")), btree_map.get("a"));
+    assert_eq!(
+        yscl_node!({ a = "2", b = "1" }),
+        Node::Map(Map::try_from(btree_map).unwrap())
+    );
+
+    let hash_map: HashMap<String, Node> = map.into();
+    assert_eq!(2, hash_map.len());
+    assert_eq!(
+        yscl_node!({ a = "2", b = "1" }),
+        Node::Map(Map::try_from(hash_map).unwrap())
+    );
+}
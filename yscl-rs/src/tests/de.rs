@@ -0,0 +1,306 @@
+use crate::prelude::*;
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Mode {
+    Fast,
+    SafeAndSlow,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Backend {
+    Memory,
+    File(String),
+    Remote { host: String, port: u16 },
+    Pair(String, u8),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Auth {
+    None,
+    Token {
+        token: String,
+        ttl_secs: u32,
+        refresh: bool,
+        weight: f64,
+    },
+    Basic {
+        user: String,
+        realm: Option<String>,
+        port: Option<i16>,
+    },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    app_name: String,
+    #[serde(default)]
+    debug: bool,
+    workers: u8,
+    ratio: f64,
+    initial: char,
+    mode: Mode,
+    backends: Vec<Backend>,
+    auth: Auth,
+    limits: HashMap<String, u32>,
+    point: (i32, i32),
+    retries: Option<u8>,
+    nickname: Option<String>,
+}
+
+#[test]
+fn deserializes_struct() {
+    let src = r#"
+appName = "demo"
+workers = "4"
+ratio = "0.5"
+initial = "黒"
+mode = "safe-and-slow"
+backends = [
+    "Memory"
+    {
+        File = "/tmp/db"
+    }
+    {
+        Remote = {
+            host = "example.com"
+            port = "443"
+        }
+    }
+    {
+        Pair = [
+            "a"
+            "7"
+        ]
+    }
+]
+auth = {
+    type = "basic"
+    user = "admin"
+    port = "-1"
+}
+limits = {
+    requests = "100"
+}
+point = [
+    "-1"
+    "2"
+]
+retries = "3"
+"#;
+    let config: Config = from_str(src).unwrap();
+    assert_eq!(
+        Config {
+            app_name: "demo".to_owned(),
+            debug: false,
+            workers: 4,
+            ratio: 0.5,
+            initial: '黒',
+            mode: Mode::SafeAndSlow,
+            backends: vec![
+                Backend::Memory,
+                Backend::File("/tmp/db".to_owned()),
+                Backend::Remote {
+                    host: "example.com".to_owned(),
+                    port: 443,
+                },
+                Backend::Pair("a".to_owned(), 7),
+            ],
+            auth: Auth::Basic {
+                user: "admin".to_owned(),
+                realm: None,
+                port: Some(-1),
+            },
+            limits: [("requests".to_owned(), 100)].into_iter().collect(),
+            point: (-1, 2),
+            retries: Some(3),
+            nickname: None,
+        },
+        config
+    );
+}
+
+#[test]
+fn deserializes_internally_tagged_enums() {
+    let auth: Vec<Auth> = from_node(&yscl_node!([
+        { type = "none" },
+        { token = "t", type = "token", ttl_secs = "3600", refresh = "true", weight = "0.5" }
+    ]))
+    .unwrap();
+    assert_eq!(
+        vec![
+            Auth::None,
+            Auth::Token {
+                token: "t".to_owned(),
+                ttl_secs: 3600,
+                refresh: true,
+                weight: 0.5,
+            }
+        ],
+        auth
+    );
+
+    // Only the form `ser` writes is presented as a number.
+    let err = from_node::<Auth>(&yscl_node!({
+        type = "token",
+        token = "t",
+        ttl_secs = "+1",
+        refresh = "false",
+        weight = "1"
+    }))
+    .unwrap_err();
+    assert_eq!("invalid type: string \"+1\", expected u32", err.to_string());
+}
+
+#[test]
+fn deserializes_untagged_enums() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Limit {
+        Count(u32),
+        Flag(bool),
+        Name(String),
+    }
+
+    let limits: Vec<Limit> = from_node(&yscl_node!(["10", "false", "ten", "010"])).unwrap();
+    assert_eq!(
+        vec![
+            Limit::Count(10),
+            Limit::Flag(false),
+            Limit::Name("ten".to_owned()),
+            Limit::Name("010".to_owned()),
+        ],
+        limits
+    );
+}
+
+#[test]
+fn deserializes_borrowed_and_unit_values() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        unit: (),
+        nested: BTreeMap<&'a str, Vec<&'a str>>,
+    }
+
+    let map = parse_doc(
+        r#"
+name = "x"
+unit = ""
+nested = {
+    a = [
+        "b"
+    ]
+}
+"#,
+    )
+    .unwrap();
+    let borrowed: Borrowed = from_map(&map).unwrap();
+    assert_eq!(
+        Borrowed {
+            name: "x",
+            unit: (),
+            nested: [("a", vec!["b"])].into_iter().collect(),
+        },
+        borrowed
+    );
+}
+
+#[test]
+fn reports_error_paths() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Server {
+        port: u16,
+        hosts: Vec<u8>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Root {
+        servers: Vec<Server>,
+    }
+
+    let err = |src: &str| from_str::<Root>(src).unwrap_err().to_string();
+
+    assert_eq!(
+        "servers[1].hosts[0]: expected u8, found \"256\" (number too large to fit in target type)",
+        err(r#"
+servers = [
+    {
+        port = "1"
+        hosts = []
+    }
+    {
+        port = "2"
+        hosts = [
+            "256"
+        ]
+    }
+]
+"#)
+    );
+    assert_eq!(
+        "servers[0]: missing field `hosts`",
+        err(r#"
+servers = [
+    {
+        port = "1"
+    }
+]
+"#)
+    );
+    assert_eq!(
+        "servers[0].port: expected atom, found list",
+        err(r#"
+servers = [
+    {
+        port = []
+        hosts = []
+    }
+]
+"#)
+    );
+    assert_eq!("missing field `servers`", err(""));
+    assert_eq!(
+        "servers: expected list, found atom",
+        err(r#"servers = "none""#)
+    );
+    assert_eq!("unexpected end of input", err(r#"servers = ["#));
+    assert_eq!(
+        Err(DeserializeError::Syntax(ParseError::UnexpectedEoi)),
+        from_str::<Root>(r#"servers = ["#).map(|_| ())
+    );
+}
+
+#[test]
+fn reports_invalid_enums() {
+    let err = from_node::<Vec<Backend>>(&yscl_node!([{ File = "a", Memory = "" }]))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        "[0]: expected atom or map with exactly one entry, found map",
+        err
+    );
+
+    let err = from_node::<Vec<Backend>>(&yscl_node!([{ Remote = { host = "h", port = "x" } }]))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        "[0].Remote.port: expected u16, found \"x\" (invalid digit found in string)",
+        err
+    );
+
+    let err = from_node::<Mode>(&yscl_node!("medium"))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        "unknown variant `medium`, expected `fast` or `safe-and-slow`",
+        err
+    );
+}
@@ -0,0 +1,166 @@
+use crate::prelude::*;
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+#[test]
+fn identical_trees_have_no_changes() {
+    let map = parse_doc(include_str!("sample_code/correct_misc/hello_world.yscl")).unwrap();
+    assert_eq!(Vec::<Change>::new(), diff(&map, &map.clone()));
+}
+
+#[test]
+fn map_changes() {
+    let old = yscl_node!({ a = "1", b = { c = "2", d = "3" }, e = "4" });
+    let new = yscl_node!({ a = "1", b = { c = "two" }, e = ["4"], f = "5" });
+    assert_eq!(
+        vec![
+            Change {
+                path: path("b.c"),
+                kind: ChangeKind::Changed {
+                    old: yscl_node!("2"),
+                    new: yscl_node!("two"),
+                },
+            },
+            Change {
+                path: path("b.d"),
+                kind: ChangeKind::Removed(yscl_node!("3")),
+            },
+            Change {
+                path: path("e"),
+                kind: ChangeKind::Changed {
+                    old: yscl_node!("4"),
+                    new: yscl_node!(["4"]),
+                },
+            },
+            Change {
+                path: path("f"),
+                kind: ChangeKind::Added(yscl_node!("5")),
+            },
+        ],
+        diff(&old, &new)
+    );
+}
+
+#[test]
+fn list_insertion_and_removal_use_lcs() {
+    let old = yscl_node!(["a", "b", "c", "d"]);
+    let new = yscl_node!(["x", "a", "c", "d", "y"]);
+    assert_eq!(
+        vec![
+            Change {
+                path: path("[0]"),
+                kind: ChangeKind::Added(yscl_node!("x")),
+            },
+            Change {
+                path: path("[1]"),
+                kind: ChangeKind::Removed(yscl_node!("b")),
+            },
+            Change {
+                path: path("[4]"),
+                kind: ChangeKind::Added(yscl_node!("y")),
+            },
+        ],
+        diff(&old, &new)
+    );
+}
+
+#[test]
+fn replaced_list_elements_are_compared_pairwise() {
+    let old = yscl_node!({ deps = [{ name = "foo", version = "1" }, "bar"] });
+    let new = yscl_node!({ deps = [{ name = "foo", version = "2" }, "bar"] });
+    assert_eq!(
+        vec![Change {
+            path: path("deps[0].version"),
+            kind: ChangeKind::Changed {
+                old: yscl_node!("1"),
+                new: yscl_node!("2"),
+            },
+        }],
+        diff(&old, &new)
+    );
+}
+
+#[test]
+fn report() {
+    let old = yscl_node!({ a = { b = "x\ny" }, list = ["1"] });
+    let new = yscl_node!({ a = {}, list = [], c = { d = ["e"] } });
+    assert_eq!(
+        "- a.b: \"x\\ny\"\n- list[0]: \"1\"\n+ c: { d = [\"e\"] }\n",
+        render_diff(&diff(&old, &new))
+    );
+    // Atoms are written as YSCL, not as Rust strings.
+    let old = yscl_node!({ a = "tab\there\u{1}" });
+    let new = yscl_node!({ a = "say \"hi\" \\ \u{2028}" });
+    assert_eq!(
+        "~ a: \"tab\\u000009here\\u000001\" -> \"say \\\"hi\\\" \\\\ \\u002028\"\n",
+        render_diff(&diff(&old, &new))
+    );
+}
+
+#[test]
+fn lcs_is_longest() {
+    use crate::diff::longest_common_subsequence;
+
+    // Reference quadratic-space LCS length.
+    fn lcs_len(old: &[Node], new: &[Node]) -> usize {
+        let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+        for i in 0..old.len() {
+            for j in 0..new.len() {
+                lengths[i + 1][j + 1] = if old[i] == new[j] {
+                    lengths[i][j] + 1
+                } else {
+                    lengths[i][j + 1].max(lengths[i + 1][j])
+                };
+            }
+        }
+        lengths[old.len()][new.len()]
+    }
+
+    let mut seed = 1u32;
+    let mut list = |len: usize| -> Vec<Node> {
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                Node::from(((seed >> 16) % 4).to_string())
+            })
+            .collect()
+    };
+    for (old_len, new_len) in [(0, 3), (1, 1), (5, 8), (13, 7), (30, 30), (64, 50)] {
+        let old = list(old_len);
+        let new = list(new_len);
+        let pairs = longest_common_subsequence(&old, &new);
+        assert_eq!(lcs_len(&old, &new), pairs.len());
+        for window in pairs.windows(2) {
+            assert!(window[0].0 < window[1].0 && window[0].1 < window[1].1);
+        }
+        for (i, j) in pairs {
+            assert_eq!(old[i], new[j]);
+        }
+    }
+}
+
+#[test]
+fn long_lists_diff_without_a_quadratic_table() {
+    let old: Vec<Node> = (0..100_000).map(|i| Node::from(i.to_string())).collect();
+    let mut new = old.clone();
+    new[50_000] = Node::from("changed");
+    new.insert(50_100, Node::from("inserted"));
+    assert_eq!(
+        vec![
+            Change {
+                path: path("[50000]"),
+                kind: ChangeKind::Changed {
+                    old: yscl_node!("50000"),
+                    new: yscl_node!("changed"),
+                },
+            },
+            Change {
+                path: path("[50100]"),
+                kind: ChangeKind::Added(yscl_node!("inserted")),
+            },
+        ],
+        diff(&List { elements: old }, &List { elements: new })
+    );
+}
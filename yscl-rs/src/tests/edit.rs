@@ -0,0 +1,118 @@
+use crate::prelude::*;
+
+fn id(s: &str) -> Identifier {
+    Identifier::new(s.to_owned()).unwrap()
+}
+
+fn sample_map() -> Map {
+    yscl_node!({ a = "1", b = "2", c = "3" }).map().unwrap()
+}
+
+#[test]
+fn insert_preserves_position_of_existing_key() {
+    let mut map = sample_map();
+    assert_eq!(
+        Some(yscl_node!("2")),
+        map.insert(id("b"), yscl_node!("two"))
+    );
+    assert_eq!(None, map.insert(id("d"), yscl_node!("4")));
+    assert_eq!(
+        yscl_node!({ a = "1", b = "two", c = "3", d = "4" }),
+        Node::Map(map)
+    );
+}
+
+#[test]
+fn try_insert_rejects_duplicate() {
+    let mut map = sample_map();
+    assert_eq!(
+        Err(EditError::DuplicateKey(id("a"))),
+        map.try_insert(id("a"), yscl_node!("x"))
+    );
+    assert_eq!(
+        Ok(&mut yscl_node!("4")),
+        map.try_insert(id("d"), yscl_node!("4"))
+    );
+}
+
+#[test]
+fn remove_preserves_order() {
+    let mut map = sample_map();
+    assert_eq!(Some(yscl_node!("2")), map.remove("b"));
+    assert_eq!(None, map.remove("b"));
+    assert_eq!(yscl_node!({ a = "1", c = "3" }), Node::Map(map));
+}
+
+#[test]
+fn get_mut() {
+    let mut map = sample_map();
+    *map.get_mut("c").unwrap() = yscl_node!([]);
+    assert_eq!(yscl_node!({ a = "1", b = "2", c = [] }), Node::Map(map));
+}
+
+#[test]
+fn rename_key() {
+    let mut map = sample_map();
+    assert_eq!(
+        Err(EditError::DuplicateKey(id("c"))),
+        map.rename_key("a", id("c"))
+    );
+    assert_eq!(
+        Err(EditError::MissingKey("z".to_owned())),
+        map.rename_key("z", id("y"))
+    );
+    assert_eq!(Ok(()), map.rename_key("a", id("a")));
+    assert_eq!(Ok(()), map.rename_key("b", id("z")));
+    assert_eq!(yscl_node!({ a = "1", z = "2", c = "3" }), Node::Map(map));
+}
+
+#[test]
+fn entry_api() {
+    let mut map = sample_map();
+    map.entry(id("a"))
+        .and_modify(|value| *value = yscl_node!("one"))
+        .or_insert(yscl_node!("unused"));
+    map.entry(id("d")).or_insert_with(|| yscl_node!("4"));
+    if let Entry::Occupied(entry) = map.entry(id("b")) {
+        assert_eq!(yscl_node!("2"), entry.remove());
+    }
+    assert_eq!(yscl_node!({ a = "one", c = "3", d = "4" }), Node::Map(map));
+}
+
+#[test]
+fn list_insert_and_remove() {
+    let mut list = yscl_node!(["a", "c"]).list().unwrap();
+    assert_eq!(Ok(()), list.insert(1, yscl_node!("b")));
+    assert_eq!(Ok(()), list.insert(3, yscl_node!("d")));
+    assert_eq!(
+        Err(EditError::IndexOutOfBounds(5, 4)),
+        list.insert(5, yscl_node!("f"))
+    );
+    assert_eq!(Ok(yscl_node!("a")), list.remove(0));
+    assert_eq!(Err(EditError::IndexOutOfBounds(3, 3)), list.remove(3));
+    assert_eq!(yscl_node!(["b", "c", "d"]), Node::List(list));
+}
+
+#[test]
+fn edit_errors_display() {
+    assert_eq!(
+        "duplicate key \"a\"",
+        EditError::DuplicateKey(id("a")).to_string()
+    );
+    assert_eq!(
+        "missing key \"z\"",
+        EditError::MissingKey("z".to_owned()).to_string()
+    );
+    assert_eq!(
+        "index 5 is out of bounds (list length is 4)",
+        EditError::IndexOutOfBounds(5, 4).to_string()
+    );
+    assert_eq!(
+        "invalid key \"bad-key\": unexpected '-' at byte 3",
+        EditError::InvalidKey("bad-key".to_owned(), 3).to_string()
+    );
+    assert_eq!(
+        "invalid key \"\"",
+        EditError::InvalidKey(String::new(), 0).to_string()
+    );
+}
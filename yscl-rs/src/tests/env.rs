@@ -0,0 +1,172 @@
+use crate::prelude::*;
+
+use std::collections::HashMap;
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn sample() -> Map {
+    yscl_node!({
+        server = { port = "80", Host = "localhost", host = "lower" },
+        hosts = ["a", { url = "b" }]
+    })
+    .map()
+    .unwrap()
+}
+
+#[test]
+fn overrides_atoms_and_list_elements() {
+    let mut map = sample();
+    let report = apply_env(
+        &mut map,
+        "APP",
+        vars(&[
+            ("APP__SERVER__PORT", "9090"),
+            ("APP__HOSTS__0", "x"),
+            ("APP__HOSTS__1__URL", "y"),
+            ("APP__SERVER__host", "exact"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        yscl_node!({
+            server = { port = "9090", Host = "localhost", host = "exact" },
+            hosts = ["x", { url = "y" }]
+        }),
+        Node::Map(map)
+    );
+    assert_eq!(
+        vec![
+            ("APP__HOSTS__0".to_owned(), "hosts[0]".parse().unwrap()),
+            (
+                "APP__HOSTS__1__URL".to_owned(),
+                "hosts[1].url".parse().unwrap()
+            ),
+            (
+                "APP__SERVER__PORT".to_owned(),
+                "server.port".parse().unwrap()
+            ),
+            (
+                "APP__SERVER__host".to_owned(),
+                "server.host".parse().unwrap()
+            ),
+        ],
+        report.applied
+    );
+    assert!(report.unmatched.is_empty());
+}
+
+#[test]
+fn parses_yscl_values() {
+    let mut map = sample();
+    apply_env(
+        &mut map,
+        "APP",
+        vars(&[
+            ("APP__SERVER", "{\n    port = \"1\"\n}"),
+            ("APP__HOSTS", "[]"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        yscl_node!({ server = { port = "1" }, hosts = [] }),
+        Node::Map(map)
+    );
+
+    let mut map = sample();
+    apply_env(&mut map, "APP", vars(&[("APP__HOSTS__0", "\"quoted\\n\"")])).unwrap();
+    assert_eq!(
+        Some(&yscl_node!("quoted\n")),
+        map["hosts"].as_ref().list().unwrap().get(0)
+    );
+}
+
+#[test]
+fn reports_unmatched_variables() {
+    let mut map = sample();
+    let report = apply_env(
+        &mut map,
+        "APP",
+        vars(&[
+            ("APP__SERVER__PROT", "1"),
+            ("APP__HOSTS__5", "1"),
+            ("APP__SERVER__PORT__X", "1"),
+            ("APPLICATION", "1"),
+            ("OTHER__SERVER__PORT", "1"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(sample(), map);
+    assert_eq!(
+        vec!["APP__HOSTS__5", "APP__SERVER__PORT__X", "APP__SERVER__PROT"],
+        report.unmatched
+    );
+}
+
+#[test]
+fn matches_keys_containing_the_separator() {
+    let mut map = yscl_node!({
+        my__key = "1",
+        trailing_ = { _leading = "2" },
+        a = { b = "3" },
+        a__b = "4",
+        c__d = "5"
+    })
+    .map()
+    .unwrap();
+    let report = apply_env(
+        &mut map,
+        "APP",
+        vars(&[
+            ("APP__MY__KEY", "x"),
+            ("APP__TRAILING____LEADING", "y"),
+            ("APP__A__B", "z"),
+            ("APP__C", "w"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        yscl_node!({
+            my__key = "x",
+            trailing_ = { _leading = "y" },
+            a = { b = "z" },
+            a__b = "4",
+            c__d = "5"
+        }),
+        Node::Map(map)
+    );
+    assert_eq!(vec!["APP__C"], report.unmatched);
+}
+
+#[test]
+fn invalid_value_leaves_map_unchanged() {
+    let mut map = sample();
+    assert_eq!(
+        Err(EnvOverlayError::InvalidValue(
+            "APP__SERVER__PORT".to_owned(),
+            ParseError::UnexpectedChar('"', 1)
+        )),
+        apply_env(
+            &mut map,
+            "APP",
+            vars(&[("APP__HOSTS__0", "ok"), ("APP__SERVER__PORT", "[\"a\"]")])
+        )
+    );
+    assert_eq!(sample(), map);
+    assert_eq!(
+        "APP__SERVER__PORT: unexpected '\"' at byte 1",
+        apply_env(&mut map, "APP", vars(&[("APP__SERVER__PORT", "[\"a\"]")]))
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "APP__HOSTS: unexpected end of input",
+        apply_env(&mut map, "APP", vars(&[("APP__HOSTS", "[")]))
+            .unwrap_err()
+            .to_string()
+    );
+}
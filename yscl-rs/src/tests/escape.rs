@@ -0,0 +1,83 @@
+use crate::prelude::*;
+
+const MODES: [EscapeMode; 3] = [
+    EscapeMode::Minimal,
+    EscapeMode::Printable,
+    EscapeMode::AsciiOnly,
+];
+
+#[test]
+fn escapes_by_mode() {
+    let value = "\" \\ \n \t é 🐑 \u{2028}";
+    assert_eq!(
+        "\\\" \\\\ \\n \t é 🐑 \u{2028}",
+        escape(value, EscapeMode::Minimal)
+    );
+    assert_eq!(
+        "\\\" \\\\ \\n \\u000009 é 🐑 \\u002028",
+        escape(value, EscapeMode::Printable)
+    );
+    assert_eq!(
+        "\\\" \\\\ \\n \\u000009 \\u0000e9 \\u01f411 \\u002028",
+        escape(value, EscapeMode::AsciiOnly)
+    );
+}
+
+#[test]
+fn round_trips_through_unescape_and_parser() {
+    let values = [
+        "",
+        "plain",
+        "\"\"\\\\\n\n",
+        "\0\u{7f}\u{feff}",
+        "黒🐑 \u{10ffff}",
+    ];
+    for value in values {
+        for mode in MODES {
+            let escaped = escape(value, mode);
+            assert_eq!(Ok(value.to_owned()), unescape(&escaped));
+
+            let src = format!("a = \"{}\"", escaped);
+            let map = parse_doc(&src).unwrap();
+            assert_eq!(Ok(value.to_owned()), map.get_as::<String>("a"));
+        }
+        assert!(escape(value, EscapeMode::AsciiOnly).is_ascii());
+    }
+}
+
+#[test]
+fn unescapes_unicode_escapes() {
+    assert_eq!(Ok("☺☺".to_owned()), unescape("\\u00263A\\u00263a"));
+    assert_eq!(Ok("\u{10ffff}".to_owned()), unescape("\\u10FFFF"));
+}
+
+#[test]
+fn rejects_invalid_text() {
+    assert_eq!(Err(ParseError::UnexpectedChar('"', 2)), unescape("ab\"c"));
+    assert_eq!(Err(ParseError::UnexpectedChar('\n', 1)), unescape("a\nb"));
+    assert_eq!(Err(ParseError::UnexpectedChar('t', 3)), unescape("é\\t"));
+    assert_eq!(Err(ParseError::UnexpectedEoi), unescape("a\\"));
+    assert_eq!(Err(ParseError::UnexpectedEoi), unescape("\\u00263"));
+    assert_eq!(
+        Err(ParseError::UnexpectedChar('g', 6)),
+        unescape("\\u0026g3")
+    );
+    // Surrogates and values above U+10FFFF are not scalar values.
+    assert_eq!(
+        Err(ParseError::UnexpectedChar('0', 7)),
+        unescape("\\u00d800")
+    );
+    assert_eq!(
+        Err(ParseError::UnexpectedChar('0', 7)),
+        unescape("\\u110000")
+    );
+}
+
+#[test]
+fn writes_with_escape_mode() {
+    let map = yscl_node!({ a = "黒\t🐑" }).map().unwrap();
+    let options = WriteOptions::default().with_escape_mode(EscapeMode::AsciiOnly);
+    let src = to_string_with(&map, &options);
+    assert_eq!("a = \"\\u009ed2\\u000009\\u01f411\"\n", src);
+    assert_eq!(Ok(map), parse_doc(&src));
+}
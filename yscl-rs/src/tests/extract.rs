@@ -0,0 +1,318 @@
+use crate::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn extracts_builtin_types() {
+    let map = yscl_node!({
+        port = "80",
+        hosts = ["a", "b"],
+        limits = { cpu = "2", memory = "512" },
+        raw = { x = ["y"] }
+    })
+    .map()
+    .unwrap();
+
+    let port: u16 = map.get("port").unwrap().extract().unwrap();
+    assert_eq!(80, port);
+    assert_eq!(
+        Ok(vec!["a".to_owned(), "b".to_owned()]),
+        map.get("hosts").unwrap().extract::<Vec<String>>()
+    );
+    assert_eq!(
+        Ok([("cpu".to_owned(), 2), ("memory".to_owned(), 512)]
+            .into_iter()
+            .collect::<BTreeMap<_, u32>>()),
+        map.get("limits").unwrap().extract()
+    );
+    assert_eq!(
+        Ok(2),
+        map.get("limits")
+            .unwrap()
+            .extract::<HashMap<String, u32>>()
+            .map(|limits| limits.len())
+    );
+    assert_eq!(
+        Ok(yscl_node!({ x = ["y"] })),
+        map.get("raw").unwrap().extract::<Node>()
+    );
+    assert_eq!(
+        Ok(yscl_node!({ x = ["y"] }).map().unwrap()),
+        map.get("raw").unwrap().extract::<Map>()
+    );
+    assert_eq!(
+        Ok(Some(80u16)),
+        map.get("port").unwrap().extract::<Option<u16>>()
+    );
+}
+
+#[test]
+fn reports_paths_of_builtin_types() {
+    let map = yscl_node!({ limits = { cpu = "two" } }).map().unwrap();
+    assert_eq!(
+        "limits.cpu: expected u32, found \"two\" (invalid digit found in string)",
+        map.extract::<BTreeMap<String, BTreeMap<String, u32>>>()
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "limits: expected list, found map",
+        map.extract::<BTreeMap<String, Vec<u32>>>()
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    use super::*;
+
+    use std::net::IpAddr;
+
+    #[derive(Debug, PartialEq)]
+    struct Percent(u8);
+
+    impl std::str::FromStr for Percent {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, String> {
+            let digits = s.strip_suffix('%').ok_or("missing %")?;
+            digits
+                .parse()
+                .map(Percent)
+                .map_err(|_| "bad digits".to_owned())
+        }
+    }
+
+    #[derive(Debug, PartialEq, FromYscl)]
+    enum Backend {
+        #[yscl(rename = "memory")]
+        Memory,
+        File(String),
+        Remote {
+            host: IpAddr,
+            port: u16,
+        },
+    }
+
+    #[derive(Debug, PartialEq, FromYscl)]
+    struct Port(u16);
+
+    #[derive(Debug, PartialEq, FromYscl)]
+    struct Server {
+        #[yscl(rename = "listen_port")]
+        port: Port,
+        #[yscl(default)]
+        verbose: bool,
+        #[yscl(default = "default_workers")]
+        workers: u8,
+        #[yscl(from_str = "a percentage")]
+        load: Percent,
+        r#type: String,
+        nickname: Option<String>,
+        backends: Vec<Backend>,
+    }
+
+    fn default_workers() -> u8 {
+        4
+    }
+
+    #[derive(Debug, PartialEq, FromYscl)]
+    struct Config<T> {
+        servers: Vec<Server>,
+        extra: Option<T>,
+    }
+
+    const SRC: &str = r#"
+servers = [
+    {
+        listen_port = "8080"
+        load = "75%"
+        type = "web"
+        backends = [
+            "memory"
+            {
+                File = "/tmp/db"
+            }
+            {
+                Remote = {
+                    host = "10.0.0.1"
+                    port = "5432"
+                }
+            }
+        ]
+        unknown_keys = "are ignored"
+    }
+]
+extra = "42"
+"#;
+
+    #[test]
+    fn extracts_derived_types() {
+        let map = parse_doc(SRC).unwrap();
+        assert_eq!(
+            Ok(Config {
+                servers: vec![Server {
+                    port: Port(8080),
+                    verbose: false,
+                    workers: 4,
+                    load: Percent(75),
+                    r#type: "web".to_owned(),
+                    nickname: None,
+                    backends: vec![
+                        Backend::Memory,
+                        Backend::File("/tmp/db".to_owned()),
+                        Backend::Remote {
+                            host: "10.0.0.1".parse().unwrap(),
+                            port: 5432,
+                        },
+                    ],
+                }],
+                extra: Some(42u32),
+            }),
+            map.extract()
+        );
+    }
+
+    #[test]
+    fn describes_from_str_fields_generically_by_default() {
+        #[derive(Debug, FromYscl)]
+        #[allow(dead_code)]
+        struct Usage {
+            #[yscl(from_str)]
+            load: Percent,
+        }
+
+        assert_eq!(
+            "load: expected a value parseable from text, found \"high\" (missing %)",
+            yscl_node!({ load = "high" })
+                .map()
+                .unwrap()
+                .extract::<Usage>()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn redacts_secret_from_str_fields() {
+        #[derive(Debug, FromYscl)]
+        struct Database {
+            #[yscl(from_str, secret)]
+            password: Secret<Percent>,
+            #[yscl(from_str = "a percentage", secret)]
+            quota: Percent,
+        }
+
+        let db = yscl_node!({ password = "50%", quota = "10%" })
+            .map()
+            .unwrap()
+            .extract::<Database>()
+            .unwrap();
+        assert_eq!(&Percent(50), db.password.expose());
+        assert_eq!(Percent(10), db.quota);
+
+        let err = yscl_node!({ password = "hunter2", quota = "10%" })
+            .map()
+            .unwrap()
+            .extract::<Database>()
+            .unwrap_err();
+        assert_eq!(
+            "password: expected a value parseable from text, found \"[REDACTED]\" (missing %)",
+            err.to_string()
+        );
+        assert!(!format!("{:?}", err).contains("hunter2"));
+
+        let err = yscl_node!({ password = "1%", quota = "hunter2" })
+            .map()
+            .unwrap()
+            .extract::<Database>()
+            .unwrap_err();
+        assert!(!format!("{:?}", err).contains("hunter2"));
+    }
+
+    fn extract_err(src: &str) -> String {
+        parse_doc(src)
+            .unwrap()
+            .extract::<Config<u32>>()
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn reports_paths_of_derived_types() {
+        assert_eq!(
+            "servers[0].listen_port: missing",
+            extract_err(
+                r#"
+servers = [
+    {
+        load = "1%"
+        type = "web"
+        backends = []
+    }
+]
+"#
+            )
+        );
+        assert_eq!(
+            "servers[0].load: expected a percentage, found \"1\" (missing %)",
+            extract_err(
+                r#"
+servers = [
+    {
+        listen_port = "1"
+        load = "1"
+        type = "web"
+        backends = []
+    }
+]
+"#
+            )
+        );
+        assert_eq!(
+            "servers[0].backends[0].Remote.host: expected IpAddr, found \"nowhere\" (invalid IP address syntax)",
+            extract_err(
+                r#"
+servers = [
+    {
+        listen_port = "1"
+        load = "1%"
+        type = "web"
+        backends = [
+            {
+                Remote = {
+                    host = "nowhere"
+                    port = "1"
+                }
+            }
+        ]
+    }
+]
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn reports_invalid_variants() {
+        let err = |node: Node| node.extract::<Backend>().unwrap_err().to_string();
+        assert_eq!(
+            ": expected one of `memory`, `File`, `Remote`, found \"Memory\" (unknown variant)",
+            err(yscl_node!("Memory"))
+        );
+        assert_eq!(
+            ": expected one of `memory`, `File`, `Remote`, found \"Disk\" (unknown variant)",
+            err(yscl_node!({ Disk = "/" }))
+        );
+        assert_eq!(
+            ": expected one of `memory`, `File`, `Remote`, found \"File, memory\" (expected exactly one entry, found 2)",
+            err(yscl_node!({ File = "/", memory = "" }))
+        );
+        assert_eq!(
+            "File: expected atom, found list",
+            err(yscl_node!({ File = [] }))
+        );
+        assert_eq!(": expected map, found list", err(yscl_node!([])));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::prelude::*;
+
+fn assert_formats(src: &str, expected: &str) {
+    let formatted = format_doc(src).unwrap();
+    assert_eq!(expected, formatted);
+    assert_eq!(parse_doc(src), parse_doc(&formatted));
+    assert_eq!(Ok(true), is_formatted(&formatted));
+}
+
+#[test]
+fn formats_sample() {
+    let src = include_str!("sample_code/correct_misc/hello_world.yscl");
+    let formatted = format_doc(src).unwrap();
+    assert_eq!(parse_doc(src), parse_doc(&formatted));
+    assert_eq!(Ok(formatted.clone()), format_doc(&formatted));
+    assert_eq!(Ok(false), is_formatted(src));
+
+    // Every comment is kept, in order.
+    let comments = |src: &str| -> Vec<String> {
+        src.lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("//"))
+            .map(str::to_owned)
+            .collect()
+    };
+    assert_eq!(comments(src), comments(&formatted));
+}
+
+#[test]
+fn normalizes_indentation_and_equals() {
+    assert_formats(
+        r#"
+  a="1"
+b    =   {
+c = [
+        "x"
+    {
+    d	=	"2"
+            }
+  ]
+     }
+"#,
+        r#"a = "1"
+b = {
+    c = [
+        "x"
+        {
+            d = "2"
+        }
+    ]
+}
+"#,
+    );
+}
+
+#[test]
+fn collapses_blank_lines() {
+    let src = r#"
+
+
+// Leading blank lines are removed.
+a = "1"
+
+
+
+b = [
+
+    "x"
+
+
+    "y"
+
+]
+
+c = {
+
+}
+"#;
+    assert_formats(
+        src,
+        r#"// Leading blank lines are removed.
+a = "1"
+
+b = [
+    "x"
+
+    "y"
+]
+
+c = {}
+"#,
+    );
+
+    let options = FmtOptions::default()
+        .with_indent("\t")
+        .with_max_blank_lines(0);
+    assert_eq!(
+        Ok(r#"// Leading blank lines are removed.
+a = "1"
+b = [
+	"x"
+	"y"
+]
+c = {}
+"#
+        .to_owned()),
+        format_doc_with(src, &options),
+    );
+}
+
+#[test]
+fn keeps_comments_in_empty_collections() {
+    assert_formats(
+        "a = {\n// Nothing yet.   \n}\nb = [\n      //\n]\nc = [ ]\n",
+        "a = {\n    // Nothing yet.\n}\nb = [\n    //\n]\nc = []\n",
+    );
+}
+
+#[test]
+fn keeps_atoms_verbatim() {
+    assert_formats(
+        r#"a   =   "  \u00263A \" \\ // not a comment  "
+"#,
+        r#"a = "  \u00263A \" \\ // not a comment  "
+"#,
+    );
+}
+
+#[test]
+fn handles_crlf_and_trailing_whitespace() {
+    assert_formats(
+        "a = \"1\"   \r\n// Hi\t\r\nb = {\r\n    c = \"2\"\r\n}",
+        "a = \"1\"\n// Hi\nb = {\n    c = \"2\"\n}\n",
+    );
+}
+
+#[test]
+fn formats_empty_documents() {
+    assert_formats("", "");
+    assert_formats("\n\n   \n", "");
+    assert_formats("\n  // Only a comment\n\n", "// Only a comment\n");
+}
+
+#[test]
+fn rejects_invalid_documents() {
+    let src =
+        include_str!("sample_code/patterns_and_antipatterns/code_comment_same_line/wrong.yscl");
+    assert_eq!(parse_doc(src).map(|_| ()), format_doc(src).map(|_| ()));
+    assert!(format_doc(src).is_err());
+    assert!(is_formatted(src).is_err());
+}
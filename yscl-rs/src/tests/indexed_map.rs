@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+fn id(s: &str) -> Identifier {
+    Identifier::new(s.to_owned()).unwrap()
+}
+
+#[test]
+fn lookup_matches_map() {
+    let map = parse_doc(include_str!("sample_code/correct_misc/hello_world.yscl")).unwrap();
+    let indexed = IndexedMap::try_from(map.clone()).unwrap();
+    for entry in &map.entries {
+        assert_eq!(map.get(&entry.key), indexed.get(&entry.key));
+    }
+    assert_eq!(None, indexed.get("nonexistent"));
+    assert_eq!(map, indexed.into_map());
+}
+
+#[test]
+fn remove_reindexes_following_entries() {
+    let map = yscl_node!({ a = "1", b = "2", c = "3", d = "4" })
+        .map()
+        .unwrap();
+    let mut indexed = IndexedMap::try_from(map).unwrap();
+    assert_eq!(Some(yscl_node!("2")), indexed.remove("b"));
+    assert_eq!(None, indexed.remove("b"));
+    assert_eq!(Some(&yscl_node!("3")), indexed.get("c"));
+    assert_eq!(Some(&yscl_node!("4")), indexed.get("d"));
+
+    *indexed.get_mut("d").unwrap() = yscl_node!("four");
+    assert_eq!(None, indexed.insert(id("b"), yscl_node!("two")));
+    assert_eq!(
+        yscl_node!({ a = "1", c = "3", d = "four", b = "two" }),
+        Node::Map(indexed.into_map())
+    );
+}
+
+#[test]
+fn rejects_duplicates() {
+    let map = Map {
+        entries: vec![
+            MapEntry {
+                key: id("a"),
+                value: yscl_node!("1"),
+            },
+            MapEntry {
+                key: id("a"),
+                value: yscl_node!("2"),
+            },
+        ],
+    };
+    assert_eq!(
+        Err(EditError::DuplicateKey(id("a"))),
+        IndexedMap::try_from(map)
+    );
+
+    let mut indexed = IndexedMap::new();
+    assert!(indexed.try_insert(id("a"), yscl_node!("1")).is_ok());
+    assert_eq!(
+        Err(EditError::DuplicateKey(id("a"))),
+        indexed.try_insert(id("a"), yscl_node!("2"))
+    );
+}
@@ -0,0 +1,137 @@
+use crate::prelude::*;
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+#[test]
+fn resolves_references_in_any_order() {
+    let map = yscl_node!({
+        urls = ["${base}/a", "${base}/b"],
+        base = "${scheme}://${host}",
+        scheme = "https",
+        host = "example.com"
+    })
+    .map()
+    .unwrap();
+    assert_eq!(
+        Ok(yscl_node!({
+            urls = ["https://example.com/a", "https://example.com/b"],
+            base = "https://example.com",
+            scheme = "https",
+            host = "example.com"
+        })
+        .map()
+        .unwrap()),
+        interpolate(&map)
+    );
+}
+
+#[test]
+fn escapes_and_lone_dollars_are_literal() {
+    let mut map = yscl_node!({
+        price = "$5",
+        template = "$${name} costs ${price}$"
+    })
+    .map()
+    .unwrap();
+    interpolate_in_place(&mut map).unwrap();
+    assert_eq!(
+        yscl_node!({
+            price = "$5",
+            template = "${name} costs $5$"
+        }),
+        Node::Map(map)
+    );
+}
+
+#[test]
+fn parser_keeps_literal_semantics() {
+    let map = parse_doc(
+        r#"
+a = "${b}"
+b = "x"
+"#,
+    )
+    .unwrap();
+    assert_eq!(yscl_node!({ a = "${b}", b = "x" }), Node::Map(map));
+}
+
+#[test]
+fn reports_every_error() {
+    let mut map = yscl_node!({
+        a = "${missing.key}",
+        b = "${list}",
+        c = "${unterminated",
+        d = "${bad..path}",
+        e = "${missing} and ${list} and ${unterminated",
+        list = ["${a}"]
+    })
+    .map()
+    .unwrap();
+    let original = map.clone();
+    assert_eq!(
+        Err(vec![
+            InterpolationError::Unresolved(path("a"), path("missing.key")),
+            InterpolationError::NotAnAtom(path("b"), path("list")),
+            InterpolationError::Unterminated(path("c")),
+            InterpolationError::InvalidReference(path("d"), "bad..path".to_owned()),
+            InterpolationError::Unresolved(path("e"), path("missing")),
+            InterpolationError::NotAnAtom(path("e"), path("list")),
+            InterpolationError::Unterminated(path("e")),
+        ]),
+        interpolate_in_place(&mut map)
+    );
+    assert_eq!(original, map);
+    assert_eq!(
+        vec![
+            "a: missing.key does not exist",
+            "b: list is not an atom",
+            "c: `${` has no matching `}`",
+            "d: invalid reference \"bad..path\"",
+            "e: missing does not exist",
+            "e: list is not an atom",
+            "e: `${` has no matching `}`",
+        ],
+        interpolate(&map)
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn detects_cycles() {
+    let map = yscl_node!({
+        a = "${b}",
+        b = "${c.d}",
+        c = { d = "${a}" },
+        e = "${e}",
+        f = "${a}"
+    })
+    .map()
+    .unwrap();
+    assert_eq!(
+        Err(vec![
+            InterpolationError::Cycle(vec![path("a"), path("b"), path("c.d")]),
+            InterpolationError::Cycle(vec![path("e")]),
+        ]),
+        interpolate(&map)
+    );
+    assert_eq!(
+        vec![
+            "a: reference cycle: a -> b -> c.d -> a",
+            "e: reference cycle: e -> e"
+        ],
+        interpolate(&map)
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "reference cycle",
+        InterpolationError::Cycle(vec![]).to_string()
+    );
+}
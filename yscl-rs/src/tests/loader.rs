@@ -0,0 +1,201 @@
+use crate::prelude::*;
+
+use std::path::{Path as FsPath, PathBuf};
+
+fn fixtures() -> PathBuf {
+    FsPath::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/sample_code/loader")
+}
+
+fn root() -> PathBuf {
+    fixtures().join("root")
+}
+
+fn canonical(relative: &str) -> PathBuf {
+    root().join(relative).canonicalize().unwrap()
+}
+
+#[test]
+fn merges_includes_in_order() {
+    let map = Loader::new(root())
+        .with_merge_options(MergeOptions::default().with_delete_marker("$delete"))
+        .load(root().join("app.yscl"))
+        .unwrap();
+    assert_eq!(
+        yscl_node!({
+            name = "app",
+            server = { host = "0.0.0.0", port = "443" },
+            region = "eu"
+        }),
+        Node::Map(map)
+    );
+}
+
+#[test]
+fn load_file_uses_parent_as_root() {
+    assert_eq!(
+        yscl_node!({
+            name = "default",
+            server = { host = "0.0.0.0", port = "80", debug = "true" }
+        }),
+        Node::Map(load_file(root().join("defaults.yscl")).unwrap()),
+    );
+
+    let err = load_file(root().join("servers/prod.yscl")).unwrap_err();
+    assert_eq!(canonical("servers/prod.yscl"), err.file);
+    assert!(matches!(
+        err.kind,
+        LoadErrorKind::Include {
+            error: IncludeErrorKind::OutsideRoot,
+            line: 2,
+            column: 5,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn detects_cycles() {
+    let err = Loader::new(root())
+        .load(root().join("cycle/a.yscl"))
+        .unwrap_err();
+    assert_eq!(
+        LoadErrorKind::Cycle(vec![
+            canonical("cycle/a.yscl"),
+            canonical("cycle/b.yscl"),
+            canonical("cycle/a.yscl"),
+        ]),
+        err.kind
+    );
+}
+
+#[test]
+fn rejects_paths_outside_root() {
+    let err = Loader::new(root())
+        .load(root().join("bad/escape.yscl"))
+        .unwrap_err();
+    assert_eq!(canonical("bad/escape.yscl"), err.file);
+    assert_eq!(
+        LoadErrorKind::Include {
+            include: canonical("bad").join("../../outside.yscl"),
+            error: IncludeErrorKind::OutsideRoot,
+            line: 2,
+            column: 5,
+        },
+        err.kind
+    );
+    assert_eq!(
+        format!(
+            "{}:2:5: cannot include {}/../../outside.yscl: outside the root directory",
+            err.file.display(),
+            canonical("bad").display()
+        ),
+        err.to_string()
+    );
+
+    let err = Loader::new(root())
+        .load(fixtures().join("outside.yscl"))
+        .unwrap_err();
+    assert_eq!(
+        LoadErrorKind::OutsideRoot(fixtures().join("outside.yscl")),
+        err.kind
+    );
+}
+
+#[test]
+fn reports_file_and_position() {
+    let err = Loader::new(root())
+        .load(root().join("bad/includes_syntax.yscl"))
+        .unwrap_err();
+    assert_eq!(canonical("bad/syntax.yscl"), err.file);
+    assert_eq!(
+        LoadErrorKind::Syntax {
+            error: ParseError::UnexpectedChar('c', 27),
+            line: 4,
+            column: 5,
+        },
+        err.kind
+    );
+    assert_eq!(
+        format!("{}:4:5: unexpected 'c'", err.file.display()),
+        err.to_string()
+    );
+}
+
+#[test]
+fn rejects_malformed_includes() {
+    let err = Loader::new(root())
+        .load(root().join("bad/not_a_list.yscl"))
+        .unwrap_err();
+    assert_eq!(
+        LoadErrorKind::InvalidInclude {
+            path: "include".parse().unwrap(),
+            line: 1,
+            column: 11,
+        },
+        err.kind
+    );
+    assert_eq!(
+        format!(
+            "{}:1:11: include must be a list of atoms",
+            err.file.display()
+        ),
+        err.to_string()
+    );
+
+    let err = Loader::new(root())
+        .load(root().join("bad/nested_include.yscl"))
+        .unwrap_err();
+    assert_eq!(
+        LoadErrorKind::InvalidInclude {
+            path: "include[1]".parse().unwrap(),
+            line: 4,
+            column: 5,
+        },
+        err.kind
+    );
+}
+
+#[test]
+fn reports_missing_includes_at_the_include_site() {
+    let err = Loader::new(root())
+        .load(root().join("bad/missing.yscl"))
+        .unwrap_err();
+    assert_eq!(canonical("bad/missing.yscl"), err.file);
+    assert_eq!(
+        LoadErrorKind::Include {
+            include: canonical("bad").join("nope.yscl"),
+            error: IncludeErrorKind::Io(std::io::ErrorKind::NotFound),
+            line: 2,
+            column: 5,
+        },
+        err.kind
+    );
+    assert!(err.to_string().starts_with(&format!(
+        "{}:2:5: cannot include {}: ",
+        err.file.display(),
+        canonical("bad").join("nope.yscl").display()
+    )));
+
+    let err = Loader::new(root())
+        .load(root().join("bad/nope.yscl"))
+        .unwrap_err();
+    assert_eq!(root().join("bad/nope.yscl"), err.file);
+    assert_eq!(LoadErrorKind::Io(std::io::ErrorKind::NotFound), err.kind);
+}
+
+#[test]
+fn ignores_comments_when_locating_includes() {
+    let err = Loader::new(root())
+        .load(root().join("bad/commented_include.yscl"))
+        .unwrap_err();
+    assert_eq!(canonical("bad/commented_include.yscl"), err.file);
+    assert_eq!(
+        LoadErrorKind::Include {
+            include: canonical("bad").join("absent.yscl"),
+            error: IncludeErrorKind::Io(std::io::ErrorKind::NotFound),
+            line: 13,
+            column: 5,
+        },
+        err.kind
+    );
+}
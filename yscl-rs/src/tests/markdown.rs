@@ -0,0 +1,171 @@
+use crate::prelude::*;
+
+use std::path::{Path as FsPath, PathBuf};
+
+fn fixtures() -> PathBuf {
+    FsPath::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/sample_code/markdown")
+}
+
+#[test]
+fn extracts_front_matter() {
+    let markdown = "---\ntitle = \"Home\"\n---\n# Home\n";
+    assert_eq!(
+        Some(Snippet {
+            src: "title = \"Home\"\n",
+            start: 4,
+            line: 2,
+            info: "",
+        }),
+        front_matter(markdown)
+    );
+    assert_eq!(
+        Some(""),
+        front_matter("---\r\n---\r\n").map(|snippet| snippet.src)
+    );
+
+    assert_eq!(None, front_matter("# Home\n---\nfoo = \"bar\"\n---\n"));
+    assert_eq!(None, front_matter("---\nfoo = \"bar\"\n"));
+    assert_eq!(None, front_matter(""));
+}
+
+#[test]
+fn extracts_fenced_blocks() {
+    let markdown = r#"---
+title = "Home"
+---
+```yscl
+a = "1"
+```
+
+~~~~ yscl ignore
+b = "2"
+```
+~~~~
+
+````markdown
+```yscl
+c = "3"
+```
+````
+
+```rust
+let d = 4;
+```
+
+   ```yscl
+   e = "5"
+   ```
+
+    ```yscl
+    not_a_fence = "6"
+    ```
+
+```yscl
+f = "7"
+"#;
+    let blocks = fenced_blocks(markdown);
+    assert_eq!(
+        vec![
+            ("a = \"1\"\n", 5, "yscl"),
+            ("b = \"2\"\n```\n", 9, "yscl ignore"),
+            ("   e = \"5\"\n", 24, "yscl"),
+            ("f = \"7\"\n", 32, "yscl"),
+        ],
+        blocks
+            .iter()
+            .map(|block| (block.src, block.line, block.info))
+            .collect::<Vec<_>>()
+    );
+    for block in &blocks {
+        assert_eq!(
+            block.src,
+            &markdown[block.start..block.start + block.src.len()]
+        );
+    }
+    assert_eq!(
+        vec!["```\n"],
+        fenced_blocks("````yscl\n```\n")
+            .iter()
+            .map(|block| block.src)
+            .collect::<Vec<_>>()
+    );
+    assert!(fenced_blocks("```yscl`\n```\n").is_empty());
+}
+
+#[test]
+fn translates_error_positions() {
+    let markdown = "# Title\n\n```yscl\nfoo = \"bar\"\n\n  foo = \"baz\"\n```\n";
+    let snippet = fenced_blocks(markdown)[0];
+    let err = snippet.parse().unwrap_err();
+    assert_eq!(
+        SnippetError {
+            error: ParseError::DuplicateKey("foo".to_owned(), 32),
+            line: 6,
+            column: 3,
+        },
+        err
+    );
+    assert_eq!(&markdown[32..35], "foo");
+    assert_eq!("6:3: duplicate key \"foo\"", err.to_string());
+
+    let err = fenced_blocks("```yscl\nfoo = [\n")[0].parse().unwrap_err();
+    assert_eq!("3:1: unexpected end of input", err.to_string());
+}
+
+#[test]
+fn checks_markdown() {
+    let markdown = r#"---
+title = "Home
+---
+
+```yscl ignore
+foo = "bar" lorem = "ipsum"
+```
+
+```yscl
+foo = "bar" lorem = "ipsum"
+```
+"#;
+    assert_eq!(
+        vec!["2:14: unexpected '\\n'", "10:13: unexpected 'l'"],
+        check_markdown(markdown)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn checks_docs_trees() {
+    let errors = check_docs(fixtures());
+    assert_eq!(
+        vec![
+            LoadError {
+                file: fixtures().join("guides/broken.md"),
+                kind: LoadErrorKind::Syntax {
+                    error: ParseError::DuplicateKey("license".to_owned(), 82),
+                    line: 9,
+                    column: 1,
+                },
+            },
+            LoadError {
+                file: fixtures().join("guides/front_matter.markdown"),
+                kind: LoadErrorKind::Syntax {
+                    error: ParseError::UnexpectedChar('\n', 21),
+                    line: 2,
+                    column: 18,
+                },
+            },
+        ],
+        errors
+    );
+
+    let err = check_docs(fixtures().join("nonexistent")).remove(0);
+    assert_eq!(LoadErrorKind::Io(std::io::ErrorKind::NotFound), err.kind);
+}
+
+#[test]
+fn learn_docs_are_valid() {
+    let learn = FsPath::new(env!("CARGO_MANIFEST_DIR")).join("../learn");
+    assert_eq!(Vec::<LoadError>::new(), check_docs(learn));
+}
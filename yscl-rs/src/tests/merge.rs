@@ -0,0 +1,168 @@
+use crate::prelude::*;
+
+fn doc(src: &str) -> Map {
+    parse_doc(src).unwrap()
+}
+
+#[test]
+fn deep_merge_preserves_base_order() {
+    let base = yscl_node!({ a = "1", b = { c = "2", d = "3" }, e = "4" })
+        .map()
+        .unwrap();
+    let upper = yscl_node!({ f = "5", b = { d = "three", g = "6" }, a = "one" })
+        .map()
+        .unwrap();
+    assert_eq!(
+        yscl_node!({
+            a = "one",
+            b = { c = "2", d = "three", g = "6" },
+            e = "4",
+            f = "5"
+        }),
+        Node::Map(merge([&base, &upper], &MergeOptions::default()))
+    );
+}
+
+#[test]
+fn three_layers_with_deletion() {
+    let defaults = doc(r#"
+server = {
+    host = "0.0.0.0"
+    port = "80"
+    tls = {
+        cert = "default.pem"
+    }
+}
+"#);
+    let site = doc(r#"
+server = {
+    port = "8080"
+    tls = "$delete"
+}
+"#);
+    let host = doc(r#"
+server = {
+    host = "10.0.0.1"
+    extra = {
+        nested = "$delete"
+        kept = "yes"
+    }
+}
+"#);
+    assert_eq!(
+        yscl_node!({
+            server = {
+                host = "10.0.0.1",
+                port = "8080",
+                extra = { kept = "yes" }
+            }
+        }),
+        Node::Map(merge(
+            [&defaults, &site, &host],
+            &MergeOptions::default().with_delete_marker("$delete")
+        ))
+    );
+}
+
+#[test]
+fn delete_marker_is_disabled_by_default() {
+    let base = yscl_node!({ a = "1" }).map().unwrap();
+    let upper = yscl_node!({ a = "$delete", b = ["$delete"] })
+        .map()
+        .unwrap();
+    assert_eq!(None, MergeOptions::default().delete_marker);
+    assert_eq!(
+        yscl_node!({ a = "$delete", b = ["$delete"] }),
+        Node::Map(merge([&base, &upper], &MergeOptions::default()))
+    );
+}
+
+#[test]
+fn delete_markers_in_lists_are_dropped() {
+    let base = yscl_node!({
+        replaced = ["a"],
+        appended = ["a"],
+        deps = [{ name = "foo" }],
+        fresh = { x = "1" }
+    })
+    .map()
+    .unwrap();
+    let upper = yscl_node!({
+        replaced = ["b", "DELETE"],
+        appended = ["DELETE", "b", { c = "DELETE", d = ["DELETE"] }],
+        deps = ["DELETE", { name = "bar", gone = "DELETE" }],
+        fresh = { x = "DELETE", y = ["DELETE", "y"] }
+    })
+    .map()
+    .unwrap();
+    let options = MergeOptions::default()
+        .with_delete_marker("DELETE")
+        .with_list_strategy("appended".parse().unwrap(), ListStrategy::Append)
+        .with_list_strategy(
+            "deps".parse().unwrap(),
+            ListStrategy::MergeByKey(Identifier::new("name".to_owned()).unwrap()),
+        );
+    assert_eq!(
+        yscl_node!({
+            replaced = ["b"],
+            appended = ["a", "b", { d = [] }],
+            deps = [{ name = "foo" }, { name = "bar" }],
+            fresh = { y = ["y"] }
+        }),
+        Node::Map(merge([&base, &upper], &options))
+    );
+}
+
+#[test]
+fn list_strategies() {
+    let base = yscl_node!({
+        replaced = ["a"],
+        appended = ["a"],
+        deps = [
+            { name = "foo", version = "1" },
+            { name = "bar", version = "1" },
+            "loose"
+        ]
+    })
+    .map()
+    .unwrap();
+    let upper = yscl_node!({
+        replaced = ["b"],
+        appended = ["b"],
+        deps = [
+            { name = "bar", version = "2" },
+            { name = "baz", version = "1" },
+            "loose"
+        ]
+    })
+    .map()
+    .unwrap();
+    let options = MergeOptions::default()
+        .with_list_strategy("appended".parse().unwrap(), ListStrategy::Append)
+        .with_list_strategy(
+            "deps".parse().unwrap(),
+            ListStrategy::MergeByKey(Identifier::new("name".to_owned()).unwrap()),
+        );
+    assert_eq!(
+        yscl_node!({
+            replaced = ["b"],
+            appended = ["a", "b"],
+            deps = [
+                { name = "foo", version = "1" },
+                { name = "bar", version = "2" },
+                "loose",
+                { name = "baz", version = "1" },
+                "loose"
+            ]
+        }),
+        Node::Map(merge([&base, &upper], &options))
+    );
+}
+
+#[test]
+fn kind_mismatch_replaces() {
+    let mut base = yscl_node!({ a = { b = "1" }, c = ["2"] }).map().unwrap();
+    let upper = yscl_node!({ a = "flat", c = { d = "3" } }).map().unwrap();
+    merge_into(&mut base, &upper, &MergeOptions::default());
+    assert_eq!(yscl_node!({ a = "flat", c = { d = "3" } }), Node::Map(base));
+}
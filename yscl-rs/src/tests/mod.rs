@@ -1,8 +1,34 @@
-use crate::*;
-
 mod assert;
 use assert::*;
 
+mod canonical;
+mod collections;
+#[cfg(feature = "serde")]
+mod de;
+mod diff;
+mod edit;
+mod env;
+mod escape;
+mod extract;
+mod fmt;
+mod indexed_map;
+mod interpolate;
+mod loader;
+mod markdown;
+mod merge;
+mod patch;
+mod query;
+mod secret;
+mod semantic;
+#[cfg(feature = "serde")]
+mod ser;
+mod shared;
+#[cfg(feature = "serde")]
+mod tree_serde;
+mod typed;
+mod visit;
+mod write;
+
 mod correct_misc {
     use super::*;
 
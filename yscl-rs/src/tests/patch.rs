@@ -0,0 +1,253 @@
+use crate::prelude::*;
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+fn sample() -> Map {
+    yscl_node!({
+        name = "app",
+        server = { port = "80", host = "localhost" },
+        licenses = ["MIT"]
+    })
+    .map()
+    .unwrap()
+}
+
+#[test]
+fn every_operation() {
+    let patch = parse_patch(
+        r#"
+operations = [
+    {
+        op = "add"
+        path = "licenses[1]"
+        value = "APACHE"
+    }
+    {
+        op = "add"
+        path = "server.tls"
+        value = {
+            enabled = "true"
+        }
+    }
+    {
+        op = "remove"
+        path = "server.host"
+    }
+    {
+        op = "replace"
+        path = "server.port"
+        value = "443"
+    }
+    {
+        op = "copy"
+        from = "name"
+        path = "server.name"
+    }
+    {
+        op = "move"
+        from = "licenses[0]"
+        path = "licenses[1]"
+    }
+    {
+        op = "test"
+        path = "licenses"
+        value = [
+            "APACHE"
+            "MIT"
+        ]
+    }
+]
+"#,
+    )
+    .unwrap();
+    let mut map = sample();
+    apply_patch(&mut map, &patch).unwrap();
+    assert_eq!(
+        yscl_node!({
+            name = "app",
+            server = { port = "443", tls = { enabled = "true" }, name = "app" },
+            licenses = ["APACHE", "MIT"]
+        }),
+        Node::Map(map)
+    );
+}
+
+#[test]
+fn failure_is_atomic_and_reports_index() {
+    let patch = Patch {
+        operations: vec![
+            PatchOperation::Replace {
+                path: path("name"),
+                value: yscl_node!("changed"),
+            },
+            PatchOperation::Test {
+                path: path("server.port"),
+                value: yscl_node!("8080"),
+            },
+        ],
+    };
+    let mut map = sample();
+    assert_eq!(
+        Err(PatchError {
+            index: 1,
+            kind: PatchErrorKind::TestFailed(path("server.port")),
+        }),
+        apply_patch(&mut map, &patch)
+    );
+    assert_eq!(sample(), map);
+}
+
+#[test]
+fn operation_errors() {
+    let cases = vec![
+        (
+            PatchOperation::Remove {
+                path: path("nonexistent"),
+            },
+            PatchErrorKind::Missing(path("nonexistent")),
+        ),
+        (
+            PatchOperation::Add {
+                path: path("a.b"),
+                value: yscl_node!("x"),
+            },
+            PatchErrorKind::Missing(path("a")),
+        ),
+        (
+            PatchOperation::Add {
+                path: path("licenses[2]"),
+                value: yscl_node!("x"),
+            },
+            PatchErrorKind::IndexOutOfBounds(path("licenses[2]"), 1),
+        ),
+        (
+            PatchOperation::Add {
+                path: path("licenses.x"),
+                value: yscl_node!("x"),
+            },
+            PatchErrorKind::WrongKind(path("licenses.x")),
+        ),
+        (
+            PatchOperation::Move {
+                from: path("server"),
+                path: path("server.inner"),
+            },
+            PatchErrorKind::MoveIntoDescendant,
+        ),
+        (
+            PatchOperation::Remove { path: Path::root() },
+            PatchErrorKind::RootTarget,
+        ),
+    ];
+    for (operation, expected) in cases {
+        let mut map = sample();
+        let patch = Patch {
+            operations: vec![operation],
+        };
+        assert_eq!(
+            Err(PatchError {
+                index: 0,
+                kind: expected,
+            }),
+            apply_patch(&mut map, &patch)
+        );
+    }
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Err(PatchParseError::UnknownOp(
+            path("operations[0].op"),
+            "delete".to_owned()
+        )),
+        parse_patch(
+            r#"
+operations = [
+    {
+        op = "delete"
+        path = "foo"
+    }
+]
+"#
+        )
+    );
+    assert_eq!(
+        Err(PatchParseError::Field(AccessError::Missing(path(
+            "operations[0].value"
+        )))),
+        parse_patch(
+            r#"
+operations = [
+    {
+        op = "add"
+        path = "foo"
+    }
+]
+"#
+        )
+    );
+    assert!(matches!(
+        parse_patch(
+            r#"
+operations = [
+    {
+        op = "remove"
+        path = "foo..bar"
+    }
+]
+"#
+        ),
+        Err(PatchParseError::Field(AccessError::InvalidValue { .. }))
+    ));
+    let message = |src: &str| parse_patch(src).unwrap_err().to_string();
+    assert_eq!(
+        "operations[0].op: unknown operation \"delete\"",
+        message(
+            r#"
+operations = [
+    {
+        op = "delete"
+        path = "foo"
+    }
+]
+"#
+        )
+    );
+    assert_eq!(
+        "operations[0].value: missing",
+        message(
+            r#"
+operations = [
+    {
+        op = "add"
+        path = "foo"
+    }
+]
+"#
+        )
+    );
+    assert_eq!("operations: missing", message(""));
+    assert_eq!("unexpected '=' at byte 0", message("="));
+    assert_eq!("unexpected end of input", message("operations = ["));
+}
+
+#[test]
+fn to_map_round_trips() {
+    let patch = Patch {
+        operations: vec![
+            PatchOperation::Add {
+                path: path("a[0]"),
+                value: yscl_node!({ b = "c" }),
+            },
+            PatchOperation::Move {
+                from: path("x"),
+                path: path("y"),
+            },
+            PatchOperation::Remove { path: path("z") },
+        ],
+    };
+    assert_eq!(Ok(patch.clone()), Patch::from_map(&patch.to_map()));
+}
@@ -0,0 +1,152 @@
+use crate::prelude::*;
+
+fn hello_world() -> Map {
+    parse_doc(include_str!("sample_code/correct_misc/hello_world.yscl")).unwrap()
+}
+
+fn matched_paths(map: &Map, src: &str) -> Vec<String> {
+    query(map, src)
+        .unwrap()
+        .into_iter()
+        .map(|matched| matched.path.to_string())
+        .collect()
+}
+
+#[test]
+fn child_keys() {
+    let map = hello_world();
+    let matches = query(&map, "$.dependencies.lorem.url").unwrap();
+    assert_eq!(1, matches.len());
+    assert_eq!(
+        "https://github.com/kylejlin/nonexistent_repo",
+        matches[0].node.atom().unwrap().value
+    );
+    assert_eq!(
+        vec!["dependencies.foo"],
+        matched_paths(&map, r#"dependencies["foo"]"#)
+    );
+    assert!(matched_paths(&map, "$.nonexistent").is_empty());
+}
+
+#[test]
+fn wildcard() {
+    let map = hello_world();
+    assert_eq!(
+        vec!["dependencies.foo", "dependencies.bar", "dependencies.lorem"],
+        matched_paths(&map, "$.dependencies.*")
+    );
+    assert_eq!(
+        vec!["licenses[0]", "licenses[1]", "licenses[2]"],
+        matched_paths(&map, "$.licenses[*]")
+    );
+}
+
+#[test]
+fn recursive_descent() {
+    let map = hello_world();
+    assert_eq!(
+        vec!["dependencies.lorem.url", "licenses[2].url"],
+        matched_paths(&map, "$..url")
+    );
+    assert_eq!(
+        vec!["licenses[2].url"],
+        matched_paths(&map, "$.licenses..url")
+    );
+}
+
+#[test]
+fn indices_and_ranges() {
+    let map = hello_world();
+    assert_eq!(vec!["licenses[1]"], matched_paths(&map, "$.licenses[1]"));
+    assert_eq!(vec!["licenses[2]"], matched_paths(&map, "$.licenses[-1]"));
+    assert_eq!(
+        vec!["licenses[0]", "licenses[1]"],
+        matched_paths(&map, "$.licenses[:-1]")
+    );
+    assert_eq!(
+        vec!["sequences[1]", "sequences[2]"],
+        matched_paths(&map, "$.sequences[1:3]")
+    );
+    assert!(matched_paths(&map, "$.licenses[3]").is_empty());
+    assert!(matched_paths(&map, "$.licenses[-4]").is_empty());
+}
+
+#[test]
+fn filters() {
+    let map = parse_doc(
+        r#"
+dependencies = {
+    foo = {
+        version = "1.2.0"
+    }
+    bar = {
+        version = "2.0.0"
+    }
+    baz = {
+        version = "1.0.0"
+        optional = "true"
+    }
+}
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        vec!["dependencies.foo", "dependencies.baz"],
+        matched_paths(&map, r#"$.dependencies[?(@.version ^= "1.")]"#)
+    );
+    assert_eq!(
+        vec!["dependencies.baz"],
+        matched_paths(&map, "$.dependencies[?(@.optional)]")
+    );
+    assert_eq!(
+        vec!["dependencies.foo", "dependencies.baz"],
+        matched_paths(&map, r#"$.dependencies[?(@.version != "2.0.0")]"#)
+    );
+    assert_eq!(
+        vec!["dependencies.bar.version", "dependencies.baz.version"],
+        matched_paths(&map, r#"$.dependencies.*[?(@ $= ".0.0")]"#)
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Err(QueryParseError::UnexpectedChar('!', 2)),
+        "$.!".parse::<Query>()
+    );
+    assert_eq!(
+        Err(QueryParseError::UnexpectedEoi),
+        "$.licenses[".parse::<Query>()
+    );
+    assert_eq!(
+        Err(QueryParseError::UnexpectedChar('<', 14)),
+        r#"$.foo[?(@.bar < "1")]"#.parse::<Query>()
+    );
+    assert_eq!(
+        "unexpected '!' at byte 2",
+        "$.!".parse::<Query>().unwrap_err().to_string()
+    );
+    assert_eq!(
+        "unexpected end of input",
+        "$.licenses[".parse::<Query>().unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn path_round_trip() {
+    let path: Path = "licenses[2].url".parse().unwrap();
+    assert_eq!("licenses[2].url", path.to_string());
+    assert_eq!(Ok(Path::root()), "".parse::<Path>());
+    assert_eq!(
+        Err(PathParseError::UnexpectedChar('0', 4)),
+        "foo.0".parse::<Path>()
+    );
+    assert_eq!(
+        "unexpected '0' at byte 4",
+        "foo.0".parse::<Path>().unwrap_err().to_string()
+    );
+    assert_eq!(
+        "unexpected end of input",
+        "foo[".parse::<Path>().unwrap_err().to_string()
+    );
+}
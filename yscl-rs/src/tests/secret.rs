@@ -0,0 +1,241 @@
+use crate::prelude::*;
+
+fn sample() -> Map {
+    yscl_node!({
+        name = "app",
+        GITHUB_TOKEN = "ghp_123",
+        database = { url = "postgres://", password = "hunter2", port = "5432" },
+        servers = [
+            { host = "a", credentials = { user = "u", pass = "p" } },
+            { host = "b" }
+        ],
+        signing_secret = ["k1", "k2"]
+    })
+    .map()
+    .unwrap()
+}
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+#[test]
+fn secret_wrapper_redacts_formatting_and_errors() {
+    let map = sample();
+    let password = map.get_as::<Secret<String>>("database.password").unwrap();
+    assert_eq!("[REDACTED]", format!("{:?}", password));
+    assert_eq!("[REDACTED]", password.to_string());
+    assert_eq!("hunter2", password.expose());
+    assert_eq!("hunter2", password.into_exposed());
+
+    let err = map.get_as::<Secret<u16>>("GITHUB_TOKEN").unwrap_err();
+    assert!(!format!("{:?}", err).contains("ghp_123"));
+    assert!(!err.to_string().contains("ghp_123"));
+    assert_eq!(
+        "GITHUB_TOKEN: expected u16, found \"[REDACTED]\" (invalid digit found in string)",
+        err.to_string()
+    );
+
+    assert_eq!(
+        Ok(5432),
+        map.get_as::<Secret<u16>>("database.port")
+            .map(Secret::into_exposed)
+    );
+}
+
+#[test]
+fn policy_matches_keys_and_paths() {
+    let policy = SecretPolicy::conventional()
+        .with_path_pattern("servers[*].credentials")
+        .with_path_pattern("*.url");
+    for secret in [
+        "GITHUB_TOKEN",
+        "database.password",
+        "database.url",
+        "servers[0].credentials",
+        "servers[0].credentials.user",
+        "signing_secret[1]",
+    ] {
+        assert!(policy.is_secret(&path(secret)), "{}", secret);
+    }
+    for public in [
+        "name",
+        "database",
+        "database.port",
+        "servers[0].host",
+        "servers",
+        "url",
+        "a.b.url",
+        "",
+    ] {
+        assert!(!policy.is_secret(&path(public)), "{}", public);
+    }
+}
+
+#[test]
+fn redact_replaces_secret_atoms() {
+    let policy = SecretPolicy::conventional().with_path_pattern("servers[*].credentials");
+    let redacted = policy.redact(&sample());
+    assert_eq!(
+        yscl_node!({
+            name = "app",
+            GITHUB_TOKEN = "[REDACTED]",
+            database = { url = "postgres://", password = "[REDACTED]", port = "5432" },
+            servers = [
+                { host = "a", credentials = { user = "[REDACTED]", pass = "[REDACTED]" } },
+                { host = "b" }
+            ],
+            signing_secret = ["[REDACTED]", "[REDACTED]"]
+        }),
+        Node::Map(redacted.clone())
+    );
+    let debug = format!("{:?}", redacted);
+    for value in ["ghp_123", "hunter2", "k1", "\"p\""] {
+        assert!(!debug.contains(value), "{}", value);
+    }
+}
+
+#[test]
+fn redact_changes_and_errors() {
+    let policy = SecretPolicy::conventional();
+    let old = sample();
+    let mut new = sample();
+    *new.get_mut("database").unwrap() = yscl_node!({ password = "new" });
+    new.remove("signing_secret");
+
+    let rendered = render_diff(&policy.redact_changes(&diff(&old, &new)));
+    assert_eq!(
+        "- database.url: \"postgres://\"\n\
+         ~ database.password: \"[REDACTED]\" -> \"[REDACTED]\"\n\
+         - database.port: \"5432\"\n\
+         - signing_secret: [\"[REDACTED]\", \"[REDACTED]\"]\n",
+        rendered
+    );
+
+    let err = policy.redact_error(old.get_as::<u8>("database.password").unwrap_err());
+    assert!(!err.to_string().contains("hunter2"));
+    let err = policy.redact_error(old.get_as::<u8>("database.port").unwrap_err());
+    assert!(err.to_string().contains("5432"));
+}
+
+#[test]
+fn redacted_wrapper_formats_trees() {
+    let policy = SecretPolicy::conventional();
+    let map = sample();
+    let node = Node::Map(map.clone());
+    let list = map.get("signing_secret").unwrap().as_ref().list().unwrap();
+    for debug in [
+        format!("{:?}", node.redacted(&policy)),
+        format!("{:#?}", node.redacted(&policy)),
+        format!("{:?}", map.redacted(&policy)),
+        format!("{:?}", map.entries[1].redacted(&policy)),
+        format!(
+            "{:?}",
+            list.redacted(&policy).with_path(path("signing_secret"))
+        ),
+    ] {
+        for value in ["ghp_123", "hunter2", "k1", "k2"] {
+            assert!(!debug.contains(value), "{}", value);
+        }
+    }
+    assert_eq!(
+        format!("{:?}", policy.redact(&map)),
+        format!("{:?}", map.redacted(&policy))
+    );
+    assert_eq!(
+        "MapEntry { key: Identifier(\"GITHUB_TOKEN\"), value: Atom(Atom { value: \"[REDACTED]\" }) }",
+        format!("{:?}", map.entries[1].redacted(&policy))
+    );
+
+    // The value is assumed to be at the root unless a path is given.
+    let value = &map.entries[1].value;
+    assert!(format!("{:?}", value.redacted(&policy)).contains("ghp_123"));
+    assert_eq!(
+        "Atom(Atom { value: \"[REDACTED]\" })",
+        format!(
+            "{:?}",
+            value.redacted(&policy).with_path(path("GITHUB_TOKEN"))
+        )
+    );
+
+    // Formatting without the wrapper is unchanged.
+    assert!(format!("{:?}", map).contains("ghp_123"));
+}
+
+#[test]
+fn redacted_wrapper_formats_shared_trees() {
+    let policy = SecretPolicy::conventional();
+    let node = SharedNode::from(Node::Map(sample()));
+    let map = node.as_map().unwrap();
+    for debug in [
+        format!("{:?}", node.redacted(&policy)),
+        format!("{:?}", map.redacted(&policy)),
+        format!("{:?}", map.entries()[1].redacted(&policy)),
+        format!(
+            "{:?}",
+            map.get("signing_secret")
+                .unwrap()
+                .redacted(&policy)
+                .with_path(path("signing_secret"))
+        ),
+    ] {
+        for value in ["ghp_123", "hunter2", "k1", "k2"] {
+            assert!(!debug.contains(value), "{}", value);
+        }
+    }
+    assert!(format!("{:?}", node.redacted(&policy)).contains("postgres://"));
+    assert!(format!("{:?}", node).contains("ghp_123"));
+}
+
+#[test]
+fn redacted_wrapper_formats_changes_and_errors() {
+    let policy = SecretPolicy::conventional();
+    let map = sample();
+    let mut new = sample();
+    *new.get_mut("GITHUB_TOKEN").unwrap() = yscl_node!("ghp_456");
+    let change = &diff(&map, &new)[0];
+    assert_eq!(
+        "~ GITHUB_TOKEN: \"[REDACTED]\" -> \"[REDACTED]\"",
+        change.redacted(&policy).to_string()
+    );
+    let debug = format!("{:?}", change.redacted(&policy));
+    assert!(!debug.contains("ghp_123") && !debug.contains("ghp_456"));
+    assert!(format!("{:?}", change).contains("ghp_456"));
+
+    let database = map.get("database").unwrap().as_ref().map().unwrap();
+    let mut new_database = database.clone();
+    *new_database.get_mut("password").unwrap() = yscl_node!("new");
+    let change = &diff(database, &new_database)[0];
+    let policy = SecretPolicy::default().with_path_pattern("database.password");
+    assert!(format!("{:?}", change.redacted(&policy)).contains("hunter2"));
+    assert!(
+        !format!("{:?}", change.redacted(&policy).with_path(path("database"))).contains("hunter2")
+    );
+
+    let err = map.get_as::<u8>("database.password").unwrap_err();
+    assert_eq!(
+        "database.password: expected u8, found \"[REDACTED]\" (invalid digit found in string)",
+        err.redacted(&policy).to_string()
+    );
+    assert!(!format!("{:?}", err.redacted(&policy)).contains("hunter2"));
+    let err = database.get_as::<u8>("password").unwrap_err();
+    assert!(
+        !format!("{:?}", err.redacted(&policy).with_path(path("database"))).contains("hunter2")
+    );
+}
+
+#[test]
+fn globs_with_many_stars_match_quickly() {
+    let text = "a".repeat(1000);
+    let policy = SecretPolicy::default()
+        .with_key_pattern(format!("{}b", "*a".repeat(50)))
+        .with_path_pattern(format!("{}b", "*a".repeat(50)));
+    let secret_path = Path::root().with_key(Identifier::new(text.clone()).unwrap());
+    assert!(!policy.is_secret(&secret_path));
+
+    let policy = SecretPolicy::default().with_key_pattern("*a*A*a*");
+    assert!(policy.is_secret(&secret_path));
+    let policy = SecretPolicy::default().with_key_pattern("a*b*c");
+    assert!(policy.is_secret(&path("aXbYbZc")));
+    assert!(!policy.is_secret(&path("aXbYbZcd")));
+}
@@ -0,0 +1,132 @@
+use crate::prelude::*;
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashSet},
+    hash::Hasher,
+};
+
+fn semantic_hash(node: &Node) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    node.semantic_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn semantic_eq_ignores_nested_map_order() {
+    let a = yscl_node!({ a = { x = "1", y = "2" }, b = [{ p = "", q = "" }] });
+    let b = yscl_node!({ b = [{ q = "", p = "" }], a = { y = "2", x = "1" } });
+    assert_ne!(a, b);
+    assert!(a.semantic_eq(&b));
+    assert_eq!(semantic_hash(&a), semantic_hash(&b));
+}
+
+#[test]
+fn semantic_eq_respects_list_order_and_content() {
+    let base = yscl_node!({ a = ["1", "2"] });
+    for other in [
+        yscl_node!({ a = ["2", "1"] }),
+        yscl_node!({ a = ["1"] }),
+        yscl_node!({ a = ["1", "2"], b = "" }),
+        yscl_node!({ b = ["1", "2"] }),
+        yscl_node!({ a = { x = "1" } }),
+        yscl_node!(["1", "2"]),
+    ] {
+        assert!(!base.semantic_eq(&other), "{:?}", other);
+    }
+}
+
+#[test]
+fn semantic_wrapper_dedupes() {
+    let set: HashSet<Semantic<Map>> = [
+        yscl_node!({ a = "1", b = "2" }),
+        yscl_node!({ b = "2", a = "1" }),
+        yscl_node!({ a = "2", b = "1" }),
+    ]
+    .into_iter()
+    .map(|node| Semantic(node.map().unwrap()))
+    .collect();
+    assert_eq!(2, set.len());
+
+    let a = yscl_node!({ a = "1", b = "2" });
+    let b = yscl_node!({ b = "2", a = "1" });
+    assert_eq!(Semantic(a.as_ref()), Semantic(b.as_ref()));
+}
+
+#[test]
+fn semantic_ord_ignores_map_order() {
+    let set: BTreeSet<Semantic<Node>> = [
+        yscl_node!([{ b = "2", a = "1" }]),
+        yscl_node!({ b = "1", a = "2" }),
+        yscl_node!({ a = "2", b = "1" }),
+        yscl_node!({ a = "1", c = "0" }),
+        yscl_node!({ a = "1" }),
+        yscl_node!("z"),
+        yscl_node!([{ a = "1", b = "2" }]),
+    ]
+    .into_iter()
+    .map(Semantic)
+    .collect();
+    assert_eq!(
+        vec![
+            yscl_node!("z"),
+            yscl_node!({ a = "1" }),
+            yscl_node!({ a = "1", c = "0" }),
+            yscl_node!({ b = "1", a = "2" }),
+            yscl_node!([{ b = "2", a = "1" }]),
+        ]
+        .into_iter()
+        .map(Semantic)
+        .collect::<Vec<_>>(),
+        set.into_iter().collect::<Vec<_>>()
+    );
+
+    // Consistent with `Eq`.
+    let a = yscl_node!({ x = { p = "1", q = "2" }, y = "3" });
+    let b = yscl_node!({ y = "3", x = { q = "2", p = "1" } });
+    let c = yscl_node!({ y = "3", x = { q = "2", p = "0" } });
+    assert_eq!(std::cmp::Ordering::Equal, a.semantic_cmp(&b));
+    assert_eq!(
+        std::cmp::Ordering::Greater,
+        Semantic(a.as_ref()).cmp(&Semantic(c.as_ref()))
+    );
+    assert_eq!(
+        std::cmp::Ordering::Greater,
+        Semantic(b.map().unwrap()).cmp(&Semantic(c.map().unwrap()))
+    );
+}
+
+#[test]
+fn ord_sorts_deterministically() {
+    let set: BTreeSet<Node> = [
+        yscl_node!(["a"]),
+        yscl_node!({ b = "1" }),
+        yscl_node!("b"),
+        yscl_node!({ a = "2" }),
+        yscl_node!("a"),
+        yscl_node!([]),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        vec![
+            yscl_node!("a"),
+            yscl_node!("b"),
+            yscl_node!({ a = "2" }),
+            yscl_node!({ b = "1" }),
+            yscl_node!([]),
+            yscl_node!(["a"]),
+        ],
+        set.into_iter().collect::<Vec<_>>()
+    );
+
+    let mut keys = [
+        Identifier::new("b".to_owned()).unwrap(),
+        Identifier::new("B".to_owned()).unwrap(),
+        Identifier::new("a".to_owned()).unwrap(),
+    ];
+    keys.sort();
+    assert_eq!(
+        vec!["B", "a", "b"],
+        keys.iter().map(|key| key.as_ref()).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,225 @@
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Backend {
+    Memory,
+    File(String),
+    Remote { host: String, port: u16 },
+    Pair(String, u8),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Auth {
+    None,
+    Token {
+        token: String,
+        ttl_secs: u32,
+        refresh: bool,
+        weight: f64,
+        offset: i8,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    debug: bool,
+    workers: u8,
+    ratio: f64,
+    initial: char,
+    backends: Vec<Backend>,
+    auth: Auth,
+    limits: BTreeMap<String, i64>,
+    point: (i32, i32),
+    retries: Option<u8>,
+    unit: (),
+    empty: Vec<u8>,
+}
+
+fn sample() -> Config {
+    Config {
+        name: "say \"hi\"\n".to_owned(),
+        debug: true,
+        workers: 4,
+        ratio: 0.25,
+        initial: '黒',
+        backends: vec![
+            Backend::Memory,
+            Backend::File("/tmp/db".to_owned()),
+            Backend::Remote {
+                host: "example.com".to_owned(),
+                port: 443,
+            },
+            Backend::Pair("a".to_owned(), 7),
+        ],
+        auth: Auth::Token {
+            token: "t".to_owned(),
+            ttl_secs: 3600,
+            refresh: true,
+            weight: 0.5,
+            offset: -2,
+        },
+        limits: [("requests".to_owned(), -100)].into_iter().collect(),
+        point: (-1, 2),
+        retries: None,
+        unit: (),
+        empty: vec![],
+    }
+}
+
+#[test]
+fn serializes_struct() {
+    let expected = r#"name = "say \"hi\"\n"
+debug = "true"
+workers = "4"
+ratio = "0.25"
+initial = "黒"
+backends = [
+    "Memory"
+    {
+        File = "/tmp/db"
+    }
+    {
+        Remote = {
+            host = "example.com"
+            port = "443"
+        }
+    }
+    {
+        Pair = [
+            "a"
+            "7"
+        ]
+    }
+]
+auth = {
+    type = "token"
+    token = "t"
+    ttl_secs = "3600"
+    refresh = "true"
+    weight = "0.5"
+    offset = "-2"
+}
+limits = {
+    requests = "-100"
+}
+point = [
+    "-1"
+    "2"
+]
+unit = ""
+empty = []
+"#;
+    assert_eq!(Ok(expected.to_owned()), crate::to_string(&sample()));
+}
+
+#[test]
+fn round_trips_through_from_str() {
+    let config = sample();
+    let src = crate::to_string(&config).unwrap();
+    assert_eq!(Ok(config), from_str::<Config>(&src));
+
+    let with_retries = Config {
+        retries: Some(3),
+        ..sample()
+    };
+    let map = to_map(&with_retries).unwrap();
+    assert_eq!(Some(&yscl_node!("3")), map.get("retries"));
+    assert_eq!(Ok(with_retries), from_map::<Config>(&map));
+}
+
+#[test]
+fn round_trips_tagged_enums_with_typed_fields() {
+    for auth in [
+        Auth::None,
+        Auth::Token {
+            token: "secret".to_owned(),
+            ttl_secs: 0,
+            refresh: false,
+            weight: -1.25,
+            offset: i8::MIN,
+        },
+    ] {
+        let src = crate::to_string(&BTreeMap::from([("auth", &auth)])).unwrap();
+        assert_eq!(
+            Ok(BTreeMap::from([("auth".to_owned(), auth)])),
+            from_str::<BTreeMap<String, Auth>>(&src)
+        );
+    }
+}
+
+#[test]
+fn serializes_nodes() {
+    assert_eq!(Ok(yscl_node!("1.5")), to_node(&1.5f32));
+    assert_eq!(Ok(yscl_node!(["a", "b"])), to_node(&["a", "b"]));
+    assert_eq!(Ok(yscl_node!("Memory")), to_node(&Backend::Memory));
+}
+
+#[test]
+fn rejects_what_yscl_cannot_express() {
+    let err = |result: Result<String, SerializeError>| result.unwrap_err().to_string();
+
+    assert_eq!(
+        "expected a map at the top level, found atom",
+        err(crate::to_string(&"x"))
+    );
+    assert_eq!(
+        "cannot write None at the top level",
+        err(crate::to_string(&None::<Config>))
+    );
+
+    let map: HashMap<u32, u32> = [(1, 2)].into_iter().collect();
+    assert_eq!("map keys must be strings", err(crate::to_string(&map)));
+
+    let map: BTreeMap<&str, &str> = [("a-b", "c")].into_iter().collect();
+    assert_eq!(
+        "invalid key \"a-b\" (keys must be identifiers)",
+        err(crate::to_string(&map))
+    );
+
+    #[derive(Serialize)]
+    struct Nested {
+        outer: BTreeMap<&'static str, Vec<Option<u8>>>,
+    }
+    let nested = Nested {
+        outer: [("inner", vec![Some(1), None])].into_iter().collect(),
+    };
+    assert_eq!(
+        "outer.inner[1]: cannot write None in a list",
+        err(crate::to_string(&nested))
+    );
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Kebab {
+        app_name: String,
+    }
+    let kebab = Kebab {
+        app_name: "x".to_owned(),
+    };
+    assert_eq!(
+        "invalid key \"app-name\" (keys must be identifiers)",
+        err(crate::to_string(&kebab))
+    );
+
+    assert_eq!(
+        Err(SerializeError {
+            path: "a[0]".parse().unwrap(),
+            message: "bytes must be valid UTF-8".to_owned(),
+        }),
+        to_map(&BTreeMap::from([("a", vec![Bytes(&[0xff])])]))
+    );
+}
+
+/// Serializes as bytes (rather than as a sequence of numbers).
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
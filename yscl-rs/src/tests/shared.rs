@@ -0,0 +1,105 @@
+use crate::prelude::*;
+
+fn sample() -> Node {
+    yscl_node!({
+        server = { host = "localhost", port = "80" },
+        plugins = ["auth", { name = "metrics" }],
+        name = "app"
+    })
+}
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+fn shared_map<'a>(node: &'a SharedNode, p: &str) -> &'a SharedMap {
+    node.resolve(&path(p)).unwrap().as_map().unwrap()
+}
+
+fn shared_list<'a>(node: &'a SharedNode, p: &str) -> &'a SharedList {
+    node.resolve(&path(p)).unwrap().as_list().unwrap()
+}
+
+#[test]
+fn round_trips_through_plain_tree() {
+    let shared = SharedNode::from(sample());
+    assert_eq!(sample(), Node::from(&shared));
+    assert_eq!(sample(), Node::from(shared));
+
+    let map = sample().map().unwrap();
+    assert_eq!(map, Map::from(SharedMap::from(map.clone())));
+}
+
+#[test]
+fn clone_shares_storage() {
+    let old = SharedNode::from(sample());
+    let new = old.clone();
+    assert!(shared_map(&old, "").ptr_eq(shared_map(&new, "")));
+}
+
+#[test]
+fn edits_copy_only_the_path() {
+    let old = SharedNode::from(sample());
+    let mut new = old.clone();
+    *new.resolve_mut(&path("plugins[1].name")).unwrap() = yscl_node!("tracing").into();
+
+    assert_eq!(sample(), Node::from(&old));
+    assert_eq!(
+        yscl_node!({
+            server = { host = "localhost", port = "80" },
+            plugins = ["auth", { name = "tracing" }],
+            name = "app"
+        }),
+        Node::from(&new)
+    );
+
+    assert!(!shared_map(&old, "").ptr_eq(shared_map(&new, "")));
+    assert!(!shared_list(&old, "plugins").ptr_eq(shared_list(&new, "plugins")));
+    assert!(!shared_map(&old, "plugins[1]").ptr_eq(shared_map(&new, "plugins[1]")));
+    assert!(shared_map(&old, "server").ptr_eq(shared_map(&new, "server")));
+}
+
+#[test]
+fn missing_path_copies_nothing() {
+    let old = SharedNode::from(sample());
+    let mut new = old.clone();
+    assert_eq!(None, new.resolve_mut(&path("server.missing")));
+    assert_eq!(None, new.resolve_mut(&path("plugins[5]")));
+    assert_eq!(None, new.resolve_mut(&path("name.x")));
+    assert!(shared_map(&old, "").ptr_eq(shared_map(&new, "")));
+}
+
+#[test]
+fn map_and_list_edits() {
+    let old = SharedNode::from(sample());
+    let mut new = old.clone();
+    let root = new.as_map_mut().unwrap();
+    let key = |s: &str| Identifier::new(s.to_owned()).unwrap();
+
+    assert_eq!(
+        Some(SharedNode::from(yscl_node!("app"))),
+        root.insert(key("name"), yscl_node!("renamed").into())
+    );
+    assert_eq!(None, root.insert(key("extra"), yscl_node!("1").into()));
+    assert!(root.remove("server").is_some());
+    assert!(!root.contains_key("server"));
+
+    let plugins = root.get_mut("plugins").unwrap().as_list_mut().unwrap();
+    plugins.push(yscl_node!("last").into());
+    plugins.insert(0, yscl_node!("first").into()).unwrap();
+    assert_eq!(
+        Err(EditError::IndexOutOfBounds(9, 4)),
+        plugins.remove(9).map(Node::from)
+    );
+    plugins.remove(1).unwrap();
+
+    assert_eq!(sample(), Node::from(&old));
+    assert_eq!(
+        yscl_node!({
+            plugins = ["first", { name = "metrics" }, "last"],
+            name = "renamed",
+            extra = "1"
+        }),
+        Node::from(&new)
+    );
+}
@@ -0,0 +1,133 @@
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+fn sample() -> Node {
+    yscl_node!({
+        name = "fibonacci",
+        license = ["MIT", { url = "https://example.com" }],
+        empty_map = {},
+        empty_list = [],
+        author = "xeklan (黒🐑)"
+    })
+}
+
+#[test]
+fn json_round_trip() {
+    let json = serde_json::to_string(&sample()).unwrap();
+    assert_eq!(
+        r#"{"name":"fibonacci","license":["MIT",{"url":"https://example.com"}],"empty_map":{},"empty_list":[],"author":"xeklan (黒🐑)"}"#,
+        json
+    );
+    assert_eq!(sample(), serde_json::from_str::<Node>(&json).unwrap());
+}
+
+#[test]
+fn json_preserves_entry_order() {
+    let node: Node = serde_json::from_str(r#"{"z":"1","a":"2","m":"3"}"#).unwrap();
+    let keys: Vec<&str> = node
+        .as_ref()
+        .map()
+        .unwrap()
+        .keys()
+        .map(|key| &**key)
+        .collect();
+    assert_eq!(vec!["z", "a", "m"], keys);
+}
+
+#[test]
+fn json_scalars_become_atoms() {
+    let node: Node =
+        serde_json::from_str(r#"{"port":8080,"ratio":0.5,"debug":true,"offset":-3}"#).unwrap();
+    assert_eq!(
+        yscl_node!({ port = "8080", ratio = "0.5", debug = "true", offset = "-3" }),
+        node
+    );
+}
+
+#[test]
+fn json_rejects_what_yscl_cannot_express() {
+    let err = |json: &str| serde_json::from_str::<Node>(json).unwrap_err().to_string();
+    assert_eq!(
+        "invalid type: null, expected a string, map, or sequence at line 1 column 4",
+        err("null")
+    );
+    assert_eq!(
+        "invalid value: string \"a-b\", expected an identifier at line 1 column 6",
+        err(r#"{"a-b":"c"}"#)
+    );
+    assert_eq!(
+        "invalid value: string \"\", expected an identifier at line 1 column 3",
+        err(r#"{"":"c"}"#)
+    );
+    assert_eq!(
+        "duplicate key \"a\" at line 1 column 17",
+        err(r#"{"a":"1","a":"2"}"#)
+    );
+}
+
+#[test]
+fn deserializes_tree_types_individually() {
+    assert_eq!(
+        Atom {
+            value: "x".to_owned()
+        },
+        serde_json::from_str::<Atom>(r#""x""#).unwrap()
+    );
+    assert_eq!(
+        yscl_node!({ a = "1" }).map().unwrap(),
+        serde_json::from_str::<Map>(r#"{"a":"1"}"#).unwrap()
+    );
+    assert_eq!(
+        yscl_node!(["1", []]).list().unwrap(),
+        serde_json::from_str::<List>(r#"["1",[]]"#).unwrap()
+    );
+    assert!(serde_json::from_str::<Map>(r#"["1"]"#).is_err());
+    assert_eq!(
+        Identifier::new("_a1".to_owned()).unwrap(),
+        serde_json::from_str::<Identifier>(r#""_a1""#).unwrap()
+    );
+    assert!(serde_json::from_str::<Identifier>(r#""1a""#).is_err());
+}
+
+#[test]
+fn bincode_round_trip() {
+    let bytes = bincode::serialize(&sample()).unwrap();
+    assert_eq!(sample(), bincode::deserialize::<Node>(&bytes).unwrap());
+
+    let map = sample().map().unwrap();
+    let bytes = bincode::serialize(&map).unwrap();
+    assert_eq!(map, bincode::deserialize::<Map>(&bytes).unwrap());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    settings: Node,
+    extra: Option<Map>,
+}
+
+#[test]
+fn nodes_can_be_fields() {
+    let message = Message {
+        id: 7,
+        settings: sample(),
+        extra: None,
+    };
+
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(message, serde_json::from_str(&json).unwrap());
+
+    let bytes = bincode::serialize(&message).unwrap();
+    assert_eq!(message, bincode::deserialize(&bytes).unwrap());
+
+    let src = crate::to_string(&message).unwrap();
+    assert_eq!(Ok(message), from_str::<Message>(&src));
+}
+
+#[test]
+fn yscl_round_trip_is_identity() {
+    let map = sample().map().unwrap();
+    assert_eq!(Ok(Node::Map(map.clone())), to_node(&map));
+    assert_eq!(Ok(map.clone()), from_map::<Map>(&map));
+}
@@ -0,0 +1,128 @@
+use crate::prelude::*;
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+fn sample() -> Map {
+    parse_doc(
+        r#"
+server = {
+    port = "8080"
+    address = "127.0.0.1:8080"
+    ratio = "0.75"
+    enabled = "false"
+    separator = ","
+    root = "/var/www"
+    timeouts = [
+        "30s"
+        "1h2m3s"
+        "250ms"
+    ]
+    hosts = [
+        "127.0.0.1"
+        "not an ip"
+    ]
+}
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn builtin_impls() {
+    let map = sample();
+    assert_eq!(Ok(8080), map.get_as::<u16>("server.port"));
+    assert_eq!(Ok(8080), map.get_as::<i64>("server.port"));
+    assert_eq!(Ok(0.75), map.get_as::<f64>("server.ratio"));
+    assert_eq!(Ok(false), map.get_bool("server.enabled"));
+    assert_eq!(Ok(','), map.get_as::<char>("server.separator"));
+    assert_eq!(
+        Ok(PathBuf::from("/var/www")),
+        map.get_as::<PathBuf>("server.root")
+    );
+    assert_eq!(
+        Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, 8080))),
+        map.get_as::<SocketAddr>("server.address")
+    );
+    assert_eq!(
+        Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        map.get_as::<IpAddr>("server.hosts[0]")
+    );
+}
+
+#[test]
+fn durations() {
+    let map = sample();
+    assert_eq!(
+        Ok(vec![
+            Duration::from_secs(30),
+            Duration::from_secs(3723),
+            Duration::from_millis(250),
+        ]),
+        map.get_list_of::<Duration>("server.timeouts")
+    );
+    assert!(Duration::from_atom("30").is_err());
+    assert!(Duration::from_atom("1s1h").is_err());
+    assert!(Duration::from_atom("1s1s").is_err());
+    assert!(Duration::from_atom("5 min").is_err());
+    assert!(Duration::from_atom("").is_err());
+}
+
+#[test]
+fn invalid_value_error() {
+    let map = sample();
+    let err = map.get_list_of::<IpAddr>("server.hosts").unwrap_err();
+    assert_eq!(
+        AccessError::InvalidValue {
+            path: "server.hosts[1]".parse().unwrap(),
+            value: "not an ip".to_owned(),
+            expected: "IpAddr",
+            reason: "invalid IP address syntax".to_owned(),
+        },
+        err
+    );
+    assert_eq!(
+        r#"server.hosts[1]: expected IpAddr, found "not an ip" (invalid IP address syntax)"#,
+        err.to_string()
+    );
+    assert!(map.get_bool("server.port").is_err());
+}
+
+#[test]
+fn structural_errors() {
+    let map = sample();
+    assert_eq!(
+        Err(AccessError::Missing("server.host".parse().unwrap())),
+        map.get_as::<String>("server.host")
+    );
+    assert_eq!(
+        Err(AccessError::WrongKind {
+            path: "server".parse().unwrap(),
+            expected: NodeKind::Atom,
+            actual: NodeKind::Map,
+        }),
+        map.get_as::<String>("server")
+    );
+    assert_eq!(
+        Err(AccessError::WrongKind {
+            path: "server.port".parse().unwrap(),
+            expected: NodeKind::List,
+            actual: NodeKind::Atom,
+        }),
+        map.get_list_of::<u16>("server.port")
+    );
+    assert_eq!(
+        Err(AccessError::InvalidPath(PathParseError::UnexpectedEoi)),
+        map.get_as::<u16>("server.")
+    );
+}
+
+#[test]
+fn node_accessors() {
+    let node = yscl_node!(["1", "2", "3"]);
+    assert_eq!(Ok(vec![1u8, 2, 3]), node.get_list_of::<u8>(""));
+    assert_eq!(Ok(3u8), node.get_as::<u8>("[2]"));
+}
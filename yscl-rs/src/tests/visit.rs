@@ -0,0 +1,138 @@
+use crate::prelude::*;
+
+fn hello_world() -> Map {
+    parse_doc(include_str!("sample_code/correct_misc/hello_world.yscl")).unwrap()
+}
+
+#[derive(Default)]
+struct EventRecorder(Vec<String>);
+
+impl<'a> Visit<'a> for EventRecorder {
+    fn visit_map(&mut self, path: &Path, map: &'a Map) {
+        self.0.push(format!("map {}", path));
+        walk_map(self, path, map);
+    }
+
+    fn visit_list(&mut self, path: &Path, list: &'a List) {
+        self.0.push(format!("list {}", path));
+        walk_list(self, path, list);
+    }
+
+    fn visit_atom(&mut self, path: &Path, _atom: &'a Atom) {
+        self.0.push(format!("atom {}", path));
+    }
+}
+
+#[test]
+fn visit_reports_paths_in_document_order() {
+    let map = yscl_node!({
+        a = "1",
+        b = {
+            c = ["2", {}]
+        }
+    })
+    .map()
+    .unwrap();
+    let mut recorder = EventRecorder::default();
+    recorder.visit_map(&Path::root(), &map);
+    assert_eq!(
+        vec![
+            "map ",
+            "atom a",
+            "map b",
+            "list b.c",
+            "atom b.c[0]",
+            "map b.c[1]",
+        ],
+        recorder.0
+    );
+}
+
+#[test]
+fn overriding_without_walking_prunes_subtree() {
+    struct SkipLists(usize);
+
+    impl<'a> Visit<'a> for SkipLists {
+        fn visit_list(&mut self, _path: &Path, _list: &'a List) {}
+
+        fn visit_atom(&mut self, _path: &Path, _atom: &'a Atom) {
+            self.0 += 1;
+        }
+    }
+
+    let mut counter = SkipLists(0);
+    counter.visit_map(&Path::root(), &hello_world());
+    // kantu_version, dependencies.foo, dependencies.bar, dependencies.lorem.url
+    assert_eq!(4, counter.0);
+}
+
+#[test]
+fn visit_mut_rewrites_atoms() {
+    struct Uppercase;
+
+    impl VisitMut for Uppercase {
+        fn visit_atom_mut(&mut self, path: &Path, atom: &mut Atom) {
+            if !path.to_string().starts_with("b") {
+                atom.value = atom.value.to_uppercase();
+            }
+        }
+    }
+
+    let mut node = yscl_node!({
+        a = "x",
+        b = "y",
+        c = ["z", { d = "w" }]
+    });
+    Uppercase.visit_node_mut(&Path::root(), &mut node);
+    assert_eq!(
+        yscl_node!({
+            a = "X",
+            b = "y",
+            c = ["Z", { d = "W" }]
+        }),
+        node
+    );
+}
+
+#[test]
+fn visit_entry_mut_sees_keys_and_replaces_values() {
+    struct RedactPasswords;
+
+    impl VisitMut for RedactPasswords {
+        fn visit_entry_mut(&mut self, path: &Path, key: &Identifier, value: &mut Node) {
+            if &**key == "password" {
+                *value = Node::from("***");
+            } else {
+                walk_entry_mut(self, path, key, value);
+            }
+        }
+    }
+
+    let mut map = yscl_node!({
+        password = "a",
+        db = { password = { nested = "b" }, user = "c" }
+    })
+    .map()
+    .unwrap();
+    RedactPasswords.visit_map_mut(&Path::root(), &mut map);
+    assert_eq!(
+        yscl_node!({
+            password = "***",
+            db = { password = "***", user = "c" }
+        }),
+        Node::Map(map)
+    );
+}
+
+#[test]
+fn depth_first_matches_visitor() {
+    let map = hello_world();
+    let mut recorder = EventRecorder::default();
+    recorder.visit_map(&Path::root(), &map);
+
+    let from_iter: Vec<String> = NodeRef::Map(&map)
+        .depth_first()
+        .map(|(path, node)| format!("{} {}", node.kind(), path))
+        .collect();
+    assert_eq!(recorder.0, from_iter);
+}
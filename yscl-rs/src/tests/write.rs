@@ -0,0 +1,214 @@
+use crate::prelude::*;
+
+fn round_trip(map: &Map) {
+    assert_eq!(Ok(map.clone()), parse_doc(&to_string(map)));
+    let tabs = WriteOptions::default().with_indent("\t");
+    assert_eq!(Ok(map.clone()), parse_doc(&to_string_with(map, &tabs)));
+}
+
+#[test]
+fn round_trips_sample() {
+    let src = include_str!("sample_code/correct_misc/hello_world.yscl");
+    round_trip(&parse_doc(src).unwrap());
+    round_trip(&Map::default());
+}
+
+#[test]
+fn writes_nested_structure() {
+    let map = yscl_node!({
+        a = "1",
+        b = { c = [{}, [], ["x", { d = "2" }]] },
+        e = {}
+    })
+    .map()
+    .unwrap();
+    let expected = r#"a = "1"
+b = {
+  c = [
+    {}
+    []
+    [
+      "x"
+      {
+        d = "2"
+      }
+    ]
+  ]
+}
+e = {}
+"#;
+    let options = WriteOptions::default().with_indent("  ");
+    assert_eq!(expected, to_string_with(&map, &options));
+    round_trip(&map);
+}
+
+#[test]
+fn escapes_atoms() {
+    let map = yscl_node!({
+        a = "quote \" backslash \\ newline \n tab \t nul \0 del \x7f",
+        b = "line sep \u{2028} bom \u{feff} emoji 🐑 kanji 黒",
+        c = "${not} // a comment"
+    })
+    .map()
+    .unwrap();
+    assert_eq!(
+        r#"a = "quote \" backslash \\ newline \n tab \u000009 nul \u000000 del \u00007f"
+b = "line sep \u002028 bom \u00feff emoji 🐑 kanji 黒"
+c = "${not} // a comment"
+"#,
+        to_string(&map)
+    );
+    round_trip(&map);
+}
+
+#[test]
+fn write_doc_writes_to_io() {
+    let map = yscl_node!({ a = ["b"] }).map().unwrap();
+    let mut out = vec![];
+    write_doc(&mut out, &map).unwrap();
+    assert_eq!(to_string(&map).as_bytes(), &out[..]);
+}
+
+fn stream_entries<W: std::io::Write>(writer: &mut YsclWriter<W>, map: &Map) {
+    for entry in &map.entries {
+        writer.key(&entry.key).unwrap();
+        stream_node(writer, &entry.value);
+    }
+}
+
+fn stream_node<W: std::io::Write>(writer: &mut YsclWriter<W>, node: &Node) {
+    match node {
+        Node::Atom(atom) => writer.atom(&atom.value).unwrap(),
+        Node::Map(map) => {
+            writer.begin_map().unwrap();
+            stream_entries(writer, map);
+            writer.end_map().unwrap();
+        }
+        Node::List(list) => {
+            writer.begin_list().unwrap();
+            for element in &list.elements {
+                stream_node(writer, element);
+            }
+            writer.end_list().unwrap();
+        }
+    }
+}
+
+#[test]
+fn streaming_writer_matches_to_string() {
+    let src = include_str!("sample_code/correct_misc/hello_world.yscl");
+    let map = parse_doc(src).unwrap();
+    let options = WriteOptions::default().with_indent("\t");
+
+    let mut writer = YsclWriter::with_options(vec![], options.clone());
+    stream_entries(&mut writer, &map);
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(to_string_with(&map, &options), out);
+
+    let writer = YsclWriter::new(vec![]);
+    assert_eq!(Ok(vec![]), writer.finish());
+}
+
+#[test]
+fn streaming_writer_writes_comments() {
+    let mut writer = YsclWriter::new(vec![]);
+    writer.comment("Top").unwrap();
+    writer.key("a").unwrap();
+    writer.begin_map().unwrap();
+    writer.comment("").unwrap();
+    writer.end_map().unwrap();
+    writer.key("b").unwrap();
+    writer.begin_list().unwrap();
+    writer.atom("x").unwrap();
+    writer.comment("After x").unwrap();
+    writer.end_list().unwrap();
+
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        r#"// Top
+a = {
+    //
+}
+b = [
+    "x"
+    // After x
+]
+"#,
+        out
+    );
+    assert_eq!(
+        Ok(yscl_node!({ a = {}, b = ["x"] }).map().unwrap()),
+        parse_doc(&out)
+    );
+    assert_eq!(Ok(true), is_formatted(&out));
+}
+
+#[test]
+fn streaming_writer_rejects_invalid_calls() {
+    let mut writer = YsclWriter::new(vec![]);
+    assert_eq!(Err(YsclWriterError::MissingKey), writer.atom("x"));
+    assert_eq!(Err(YsclWriterError::UnexpectedEnd), writer.end_map());
+    assert_eq!(Err(YsclWriterError::UnexpectedEnd), writer.end_list());
+    for key in ["", "1a", "a-b", "a b", "é"] {
+        assert_eq!(
+            Err(YsclWriterError::InvalidKey(key.to_owned())),
+            writer.key(key)
+        );
+    }
+    assert_eq!(
+        Err(YsclWriterError::InvalidComment("a\nb".to_owned())),
+        writer.comment("a\nb")
+    );
+
+    writer.key("a").unwrap();
+    assert_eq!(
+        Err(YsclWriterError::MissingValue("a".to_owned())),
+        writer.key("b")
+    );
+    assert_eq!(
+        Err(YsclWriterError::MissingValue("a".to_owned())),
+        writer.comment("c")
+    );
+    writer.begin_list().unwrap();
+    assert_eq!(
+        Err(YsclWriterError::UnexpectedKey("b".to_owned())),
+        writer.key("b")
+    );
+    assert_eq!(Err(YsclWriterError::UnexpectedEnd), writer.end_map());
+    writer.begin_map().unwrap();
+    writer.key("c").unwrap();
+    assert_eq!(
+        Err(YsclWriterError::MissingValue("c".to_owned())),
+        writer.end_map()
+    );
+    writer.atom("1").unwrap();
+    assert_eq!(
+        Err(YsclWriterError::DuplicateKey("c".to_owned())),
+        writer.key("c")
+    );
+    writer.end_map().unwrap();
+    writer.end_list().unwrap();
+    assert_eq!(
+        Err(YsclWriterError::DuplicateKey("a".to_owned())),
+        writer.key("a")
+    );
+
+    // The failed calls wrote nothing.
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        Ok(yscl_node!({ a = [{ c = "1" }] }).map().unwrap()),
+        parse_doc(&out)
+    );
+
+    let mut writer = YsclWriter::new(vec![]);
+    writer.key("a").unwrap();
+    writer.begin_map().unwrap();
+    assert_eq!(Err(YsclWriterError::Unfinished), writer.finish());
+
+    let mut writer = YsclWriter::new(vec![]);
+    writer.key("a").unwrap();
+    assert_eq!(
+        Err(YsclWriterError::MissingValue("a".to_owned())),
+        writer.finish()
+    );
+}
@@ -0,0 +1,400 @@
+//! Standard iterator, indexing, and conversion trait impls
+//! for the tree types.
+
+use super::*;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{Index, IndexMut},
+};
+
+impl Map {
+    /// Returns an iterator over the entries, in document order.
+    pub fn iter(&self) -> MapIter<'_> {
+        MapIter(self.entries.iter())
+    }
+
+    /// Returns an iterator over the entries, in document order,
+    /// with mutable references to the values.
+    pub fn iter_mut(&mut self) -> MapIterMut<'_> {
+        MapIterMut(self.entries.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &Identifier> + ExactSizeIterator {
+        self.entries.iter().map(|entry| &entry.key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Node> + ExactSizeIterator {
+        self.entries.iter().map(|entry| &entry.value)
+    }
+
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Node> + ExactSizeIterator {
+        self.entries.iter_mut().map(|entry| &mut entry.value)
+    }
+}
+
+impl List {
+    pub fn iter(&self) -> std::slice::Iter<'_, Node> {
+        self.elements.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Node> {
+        self.elements.iter_mut()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapIter<'a>(std::slice::Iter<'a, MapEntry>);
+
+#[derive(Debug)]
+pub struct MapIterMut<'a>(std::slice::IterMut<'a, MapEntry>);
+
+#[derive(Clone, Debug)]
+pub struct MapIntoIter(std::vec::IntoIter<MapEntry>);
+
+impl<'a> Iterator for MapIter<'a> {
+    type Item = (&'a Identifier, &'a Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for MapIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl ExactSizeIterator for MapIter<'_> {}
+
+impl<'a> Iterator for MapIterMut<'a> {
+    type Item = (&'a Identifier, &'a mut Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|entry| (&entry.key, &mut entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for MapIterMut<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_back()
+            .map(|entry| (&entry.key, &mut entry.value))
+    }
+}
+
+impl ExactSizeIterator for MapIterMut<'_> {}
+
+impl Iterator for MapIntoIter {
+    type Item = (Identifier, Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|entry| (entry.key, entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for MapIntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|entry| (entry.key, entry.value))
+    }
+}
+
+impl ExactSizeIterator for MapIntoIter {}
+
+impl IntoIterator for Map {
+    type Item = (Identifier, Node);
+    type IntoIter = MapIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MapIntoIter(self.entries.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a Identifier, &'a Node);
+    type IntoIter = MapIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a Identifier, &'a mut Node);
+    type IntoIter = MapIterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for List {
+    type Item = Node;
+    type IntoIter = std::vec::IntoIter<Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a List {
+    type Item = &'a Node;
+    type IntoIter = std::slice::Iter<'a, Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut List {
+    type Item = &'a mut Node;
+    type IntoIter = std::slice::IterMut<'a, Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter_mut()
+    }
+}
+
+/// Keys that appear more than once are handled like [`Map::insert`]:
+/// the last value wins, but the entry keeps the position
+/// of the key's first occurrence.
+impl<V> FromIterator<(Identifier, V)> for Map
+where
+    V: Into<Node>,
+{
+    fn from_iter<T: IntoIterator<Item = (Identifier, V)>>(iter: T) -> Self {
+        let mut map = Map::default();
+        map.extend(iter);
+        map
+    }
+}
+
+/// Behaves like [`Map::insert`] for each item.
+impl<V> Extend<(Identifier, V)> for Map
+where
+    V: Into<Node>,
+{
+    fn extend<T: IntoIterator<Item = (Identifier, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value.into());
+        }
+    }
+}
+
+impl<V> FromIterator<V> for List
+where
+    V: Into<Node>,
+{
+    fn from_iter<T: IntoIterator<Item = V>>(iter: T) -> Self {
+        Self {
+            elements: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<V> Extend<V> for List
+where
+    V: Into<Node>,
+{
+    fn extend<T: IntoIterator<Item = V>>(&mut self, iter: T) {
+        self.elements.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+/// Panics if the key does not exist.
+impl<K> Index<&K> for Map
+where
+    K: ?Sized + AsRef<str>,
+{
+    type Output = Node;
+
+    fn index(&self, key: &K) -> &Self::Output {
+        self.get(key)
+            .unwrap_or_else(|| panic!("Key {:?} does not exist", key.as_ref()))
+    }
+}
+
+/// Panics if the key does not exist.
+impl<K> IndexMut<&K> for Map
+where
+    K: ?Sized + AsRef<str>,
+{
+    fn index_mut(&mut self, key: &K) -> &mut Self::Output {
+        let key = key.as_ref();
+        self.get_mut(key)
+            .unwrap_or_else(|| panic!("Key {:?} does not exist", key))
+    }
+}
+
+impl Index<usize> for List {
+    type Output = Node;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.elements[index]
+    }
+}
+
+impl IndexMut<usize> for List {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.elements[index]
+    }
+}
+
+impl From<Vec<Node>> for List {
+    fn from(elements: Vec<Node>) -> Self {
+        Self { elements }
+    }
+}
+
+impl From<List> for Vec<Node> {
+    fn from(list: List) -> Self {
+        list.elements
+    }
+}
+
+impl From<String> for Atom {
+    fn from(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(value: &str) -> Self {
+        Self {
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl From<String> for Node {
+    fn from(value: String) -> Self {
+        Node::Atom(value.into())
+    }
+}
+
+impl From<&str> for Node {
+    fn from(value: &str) -> Self {
+        Node::Atom(value.into())
+    }
+}
+
+impl From<Vec<Node>> for Node {
+    fn from(elements: Vec<Node>) -> Self {
+        Node::List(List { elements })
+    }
+}
+
+/// Returns the byte position of the first illegal character on failure,
+/// like [`Identifier::new`].
+impl TryFrom<String> for Identifier {
+    type Error = usize;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Identifier::new(s)
+    }
+}
+
+/// Returns the byte position of the first illegal character on failure,
+/// like [`Identifier::new`].
+impl TryFrom<&str> for Identifier {
+    type Error = usize;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Identifier::new(s.to_owned())
+    }
+}
+
+/// Returns [`EditError::InvalidKey`] if a key is not a valid identifier,
+/// and [`EditError::DuplicateKey`] if a key appears more than once.
+///
+/// ## Example
+/// ```rust
+/// use yscl::prelude::*;
+///
+/// let map = Map::try_from(vec![
+///     ("name".to_owned(), Node::from("fibonacci")),
+///     ("license".to_owned(), Node::from(vec![Node::from("MIT")])),
+/// ])
+/// .unwrap();
+/// assert_eq!(
+///     Node::Map(map),
+///     yscl_node!({ name = "fibonacci", license = ["MIT"] }),
+/// );
+///
+/// assert_eq!(
+///     Map::try_from(vec![("bad-key".to_owned(), Node::from("x"))]),
+///     Err(EditError::InvalidKey("bad-key".to_owned(), 3)),
+/// );
+/// ```
+impl TryFrom<Vec<(String, Node)>> for Map {
+    type Error = EditError;
+
+    fn try_from(pairs: Vec<(String, Node)>) -> Result<Self, Self::Error> {
+        let mut map = Map {
+            entries: Vec::with_capacity(pairs.len()),
+        };
+        for (key, value) in pairs {
+            let key = match Identifier::new(key.clone()) {
+                Ok(key) => key,
+                Err(i) => return Err(EditError::InvalidKey(key, i)),
+            };
+            map.try_insert(key, value)?;
+        }
+        Ok(map)
+    }
+}
+
+/// Returns [`EditError::InvalidKey`] if a key is not a valid identifier.
+/// Entries are sorted by key.
+impl TryFrom<BTreeMap<String, Node>> for Map {
+    type Error = EditError;
+
+    fn try_from(btree_map: BTreeMap<String, Node>) -> Result<Self, Self::Error> {
+        Map::try_from(btree_map.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// Returns [`EditError::InvalidKey`] if a key is not a valid identifier.
+/// Since a `HashMap` has no inherent order,
+/// entries are sorted by key so the result is deterministic.
+impl<S> TryFrom<HashMap<String, Node, S>> for Map {
+    type Error = EditError;
+
+    fn try_from(hash_map: HashMap<String, Node, S>) -> Result<Self, Self::Error> {
+        let mut pairs: Vec<_> = hash_map.into_iter().collect();
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Map::try_from(pairs)
+    }
+}
+
+impl From<Map> for BTreeMap<String, Node> {
+    fn from(map: Map) -> Self {
+        map.into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+}
+
+impl<S> From<Map> for HashMap<String, Node, S>
+where
+    S: std::hash::BuildHasher + Default,
+{
+    fn from(map: Map) -> Self {
+        map.into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+}
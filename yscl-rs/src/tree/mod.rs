@@ -0,0 +1,589 @@
+use std::{borrow::Borrow, fmt, ops::Deref};
+
+mod collections;
+pub use collections::{MapIntoIter, MapIter, MapIterMut};
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+/// Like the derived `PartialEq`, the derived `Ord` is sensitive to
+/// map entry order.
+/// Atoms sort before maps, which sort before lists;
+/// nodes of the same kind are compared lexicographically
+/// (atoms by value, maps by entries, lists by elements).
+///
+/// To compare nodes while ignoring map entry order, use
+/// [`Node::semantic_eq`] or [`Semantic`](crate::semantic::Semantic).
+///
+/// With the `serde` feature, the tree types implement `Serialize` and
+/// `Deserialize`, so nodes can be embedded in other formats.
+/// In human-readable formats (like JSON), atoms are strings,
+/// maps are maps, and lists are sequences, like `serde_json::Value`;
+/// other scalars (like JSON numbers) are read as atoms containing their text.
+/// Other formats (like bincode) write a node as an enum
+/// with `Atom`, `Map`, and `List` variants.
+/// Map keys must be identifiers, and must not repeat.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Node {
+    Atom(Atom),
+    Map(Map),
+    List(List),
+}
+
+impl Node {
+    pub fn atom(self) -> Option<Atom> {
+        match self {
+            Node::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    pub fn map(self) -> Option<Map> {
+        match self {
+            Node::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn list(self) -> Option<List> {
+        match self {
+            Node::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
+impl Node {
+    pub fn as_ref(&self) -> NodeRef<'_> {
+        match self {
+            Node::Atom(atom) => NodeRef::Atom(atom),
+            Node::Map(map) => NodeRef::Map(map),
+            Node::List(list) => NodeRef::List(list),
+        }
+    }
+}
+
+impl From<Atom> for Node {
+    fn from(atom: Atom) -> Self {
+        Node::Atom(atom)
+    }
+}
+
+impl From<Map> for Node {
+    fn from(map: Map) -> Self {
+        Node::Map(map)
+    }
+}
+
+impl From<List> for Node {
+    fn from(list: List) -> Self {
+        Node::List(list)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeRef<'a> {
+    Atom(&'a Atom),
+    Map(&'a Map),
+    List(&'a List),
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn atom(self) -> Option<&'a Atom> {
+        match self {
+            NodeRef::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    pub fn map(self) -> Option<&'a Map> {
+        match self {
+            NodeRef::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn list(self) -> Option<&'a List> {
+        match self {
+            NodeRef::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a Node> for NodeRef<'a> {
+    fn from(node: &'a Node) -> Self {
+        node.as_ref()
+    }
+}
+
+impl<'a> From<&'a Atom> for NodeRef<'a> {
+    fn from(atom: &'a Atom) -> Self {
+        NodeRef::Atom(atom)
+    }
+}
+
+impl<'a> From<&'a Map> for NodeRef<'a> {
+    fn from(map: &'a Map) -> Self {
+        NodeRef::Map(map)
+    }
+}
+
+impl<'a> From<&'a List> for NodeRef<'a> {
+    fn from(list: &'a List) -> Self {
+        NodeRef::List(list)
+    }
+}
+
+impl NodeRef<'_> {
+    pub fn to_owned(self) -> Node {
+        match self {
+            NodeRef::Atom(atom) => Node::Atom(atom.clone()),
+            NodeRef::Map(map) => Node::Map(map.clone()),
+            NodeRef::List(list) => Node::List(list.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Atom {
+    /// This is the _value_ of the atom, not the _source_.
+    /// For example, the YSCL atom `"\""` will be represented
+    /// by the Rust expression `Atom { value: "\"".to_string() }`,
+    /// NOT `Atom { value: "\"\\\"\"".to_string() }`.
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Map {
+    pub entries: Vec<MapEntry>,
+}
+
+impl Map {
+    /// Get the value corresponding to the given key
+    /// if one exists.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use yscl::prelude::*;
+    /// let map = parse_doc(r#"
+    /// kantu_version = "1.0.0"
+    /// name = "fibonacci"
+    /// license = [
+    ///     "MIT"
+    ///     "Apache-2.0"
+    /// ]
+    /// dependencies = {
+    ///     yscl = "1.0.0"
+    ///     json = "1.0.0"
+    /// }
+    /// author = "xeklan (黒🐑)"
+    /// "#).unwrap();
+    /// assert_eq!(
+    ///     map.get("kantu_version").unwrap().as_ref().atom().unwrap().value,
+    ///     "1.0.0",
+    /// );
+    /// assert_eq!(
+    ///     map.get("repository"),
+    ///     None,
+    /// );
+    /// ```
+    pub fn get<K>(&self, key: &K) -> Option<&Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.entries.iter().find_map(|entry| {
+            if *entry.key == *key.as_ref() {
+                Some(&entry.value)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn get_mut<K>(&mut self, key: &K) -> Option<&mut Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = self.position(key)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    pub fn contains_key<K>(&self, key: &K) -> bool
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Returns the index of the entry with the given key
+    /// if one exists.
+    pub fn position<K>(&self, key: &K) -> Option<usize>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        self.entries
+            .iter()
+            .position(|entry| *entry.key == *key.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sets the value of `key` to `value`.
+    ///
+    /// If the key already exists, its value is replaced
+    /// (without changing the entry's position)
+    /// and the old value is returned.
+    /// Otherwise, a new entry is appended to the end of the map.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use yscl::prelude::*;
+    /// let mut map = parse_doc(r#"
+    /// name = "fibonacci"
+    /// version = "1.0.0"
+    /// "#).unwrap();
+    /// let name = Identifier::new("name".to_owned()).unwrap();
+    /// let license = Identifier::new("license".to_owned()).unwrap();
+    ///
+    /// let old = map.insert(name, yscl_node!("fib"));
+    /// assert_eq!(old, Some(yscl_node!("fibonacci")));
+    /// assert_eq!(map.insert(license, yscl_node!("MIT")), None);
+    ///
+    /// assert_eq!(
+    ///     Node::Map(map),
+    ///     yscl_node!({ name = "fib", version = "1.0.0", license = "MIT" }),
+    /// );
+    /// ```
+    pub fn insert(&mut self, key: Identifier, value: Node) -> Option<Node> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Appends a new entry to the end of the map.
+    ///
+    /// Unlike [`Map::insert`], this will **not** replace an existing value.
+    /// Instead, it returns [`EditError::DuplicateKey`] if the key already exists.
+    pub fn try_insert(&mut self, key: Identifier, value: Node) -> Result<&mut Node, EditError> {
+        match self.entry(key) {
+            Entry::Occupied(entry) => Err(EditError::DuplicateKey(entry.key().clone())),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
+    }
+
+    /// Removes the entry with the given key, returning its value.
+    /// The order of the remaining entries is preserved.
+    pub fn remove<K>(&mut self, key: &K) -> Option<Node>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index).value)
+    }
+
+    /// Changes the key of an existing entry without changing its position.
+    ///
+    /// Returns [`EditError::MissingKey`] if `old_key` does not exist,
+    /// and [`EditError::DuplicateKey`] if `new_key` is already used
+    /// by a different entry.
+    pub fn rename_key<K>(&mut self, old_key: &K, new_key: Identifier) -> Result<(), EditError>
+    where
+        K: ?Sized + AsRef<str>,
+    {
+        let Some(index) = self.position(old_key) else {
+            return Err(EditError::MissingKey(old_key.as_ref().to_owned()));
+        };
+        if let Some(other_index) = self.position(&new_key) {
+            if other_index != index {
+                return Err(EditError::DuplicateKey(new_key));
+            }
+        }
+        self.entries[index].key = new_key;
+        Ok(())
+    }
+
+    /// Gets the entry for the given key for in-place manipulation.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use yscl::prelude::*;
+    /// let mut map = Map { entries: vec![] };
+    /// let counter = Identifier::new("counter".to_owned()).unwrap();
+    ///
+    /// for _ in 0..3 {
+    ///     let value = map.entry(counter.clone()).or_insert(yscl_node!("0"));
+    ///     let Node::Atom(atom) = value else { unreachable!() };
+    ///     atom.value = (atom.value.parse::<u32>().unwrap() + 1).to_string();
+    /// }
+    ///
+    /// assert_eq!(Node::Map(map), yscl_node!({ counter = "3" }));
+    /// ```
+    pub fn entry(&mut self, key: Identifier) -> Entry<'_> {
+        match self.position(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+}
+
+/// A view into a single entry of a [`Map`],
+/// which may either be occupied or vacant.
+#[derive(Debug)]
+pub enum Entry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+#[derive(Debug)]
+pub struct OccupiedEntry<'a> {
+    map: &'a mut Map,
+    index: usize,
+}
+
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    map: &'a mut Map,
+    key: Identifier,
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &Identifier {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: Node) -> &'a mut Node {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut Node
+    where
+        F: FnOnce() -> Node,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut Node),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn key(&self) -> &Identifier {
+        &self.map.entries[self.index].key
+    }
+
+    pub fn get(&self) -> &Node {
+        &self.map.entries[self.index].value
+    }
+
+    pub fn get_mut(&mut self) -> &mut Node {
+        &mut self.map.entries[self.index].value
+    }
+
+    pub fn into_mut(self) -> &'a mut Node {
+        &mut self.map.entries[self.index].value
+    }
+
+    /// Replaces the entry's value, returning the old value.
+    pub fn insert(&mut self, value: Node) -> Node {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    /// The order of the remaining entries is preserved.
+    pub fn remove(self) -> Node {
+        self.map.entries.remove(self.index).value
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    pub fn key(&self) -> &Identifier {
+        &self.key
+    }
+
+    /// Appends a new entry to the end of the map.
+    pub fn insert(self, value: Node) -> &'a mut Node {
+        self.map.entries.push(MapEntry {
+            key: self.key,
+            value,
+        });
+        &mut self
+            .map
+            .entries
+            .last_mut()
+            .expect("Entries should be non-empty after a push")
+            .value
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct List {
+    pub elements: Vec<Node>,
+}
+
+impl List {
+    pub fn get(&self, index: usize) -> Option<&Node> {
+        self.elements.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Node> {
+        self.elements.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn push(&mut self, element: Node) {
+        self.elements.push(element);
+    }
+
+    /// Inserts `element` at position `index`,
+    /// shifting all elements after it to the right.
+    ///
+    /// Returns [`EditError::IndexOutOfBounds`] if `index > len`.
+    pub fn insert(&mut self, index: usize, element: Node) -> Result<(), EditError> {
+        if index > self.elements.len() {
+            return Err(EditError::IndexOutOfBounds(index, self.elements.len()));
+        }
+        self.elements.insert(index, element);
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index`,
+    /// shifting all elements after it to the left.
+    ///
+    /// Returns [`EditError::IndexOutOfBounds`] if `index >= len`.
+    pub fn remove(&mut self, index: usize) -> Result<Node, EditError> {
+        if index >= self.elements.len() {
+            return Err(EditError::IndexOutOfBounds(index, self.elements.len()));
+        }
+        Ok(self.elements.remove(index))
+    }
+}
+
+/// An error returned when an edit would produce
+/// an invalid tree or refers to a nonexistent location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// The key already exists in the map.
+    DuplicateKey(Identifier),
+    /// The key does not exist in the map.
+    MissingKey(String),
+    /// The index and the length of the list.
+    IndexOutOfBounds(usize, usize),
+    /// The key is not a valid identifier.
+    /// Contains the key and the byte position of its first illegal character.
+    InvalidKey(String, usize),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::DuplicateKey(key) => write!(f, "duplicate key {:?}", &**key),
+            EditError::MissingKey(key) => write!(f, "missing key {:?}", key),
+            EditError::IndexOutOfBounds(index, len) => write!(
+                f,
+                "index {} is out of bounds (list length is {})",
+                index, len
+            ),
+            EditError::InvalidKey(key, i) => match key.get(*i..).and_then(|s| s.chars().next()) {
+                Some(c) => write!(f, "invalid key {:?}: unexpected {:?} at byte {}", key, c, i),
+                None => write!(f, "invalid key {:?}", key),
+            },
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MapEntry {
+    pub key: Identifier,
+    pub value: Node,
+}
+
+/// A string consisting of one or more ASCII letters,
+/// digits, or underscores.
+/// The initial character may **not** be a digit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Identifier(String);
+
+impl From<Identifier> for String {
+    fn from(identifier: Identifier) -> Self {
+        identifier.0
+    }
+}
+
+impl Deref for Identifier {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Identifier {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Identifier {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Identifier {
+    /// If the input contains an illegal character, returns the index of the first illegal character.
+    /// If the input is empty, returns 0.
+    ///
+    /// Legal characters are ASCII letters (both uppercase and lowercase), digits, and the underscore.
+    pub fn new(s: String) -> Result<Self, usize> {
+        match s.chars().next() {
+            None => return Err(0),
+            Some(first) if first.is_ascii_digit() => return Err(0),
+            Some(_) => {}
+        }
+
+        let bad_char_pos = s.char_indices().find_map(|(i, c)| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                None
+            } else {
+                Some(i)
+            }
+        });
+        if let Some(i) = bad_char_pos {
+            Err(i)
+        } else {
+            Ok(Self(s))
+        }
+    }
+}
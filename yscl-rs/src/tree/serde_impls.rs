@@ -0,0 +1,288 @@
+//! `Serialize` and `Deserialize` impls for the tree types.
+//! See [`Node`] for the format.
+
+use super::*;
+
+use serde::{
+    de::{self, EnumAccess, MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+const VARIANTS: &[&str] = &["Atom", "Map", "List"];
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return match self {
+                Node::Atom(atom) => atom.serialize(serializer),
+                Node::Map(map) => map.serialize(serializer),
+                Node::List(list) => list.serialize(serializer),
+            };
+        }
+        match self {
+            Node::Atom(atom) => serializer.serialize_newtype_variant("Node", 0, "Atom", atom),
+            Node::Map(map) => serializer.serialize_newtype_variant("Node", 1, "Map", map),
+            Node::List(list) => serializer.serialize_newtype_variant("Node", 2, "List", list),
+        }
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for entry in &self.entries {
+            map.serialize_entry(&entry.key, &entry.value)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for List {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.elements)
+    }
+}
+
+impl Serialize for Identifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(NodeVisitor)
+        } else {
+            deserializer.deserialize_enum("Node", VARIANTS, NodeVisitor)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Atom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_string(AtomVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for List {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ListVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Identifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_string(IdentifierVisitor)
+    }
+}
+
+/// Accepts strings, and any other scalar as its string form
+/// (just as [`ser`](crate::ser) writes scalars as atoms).
+struct AtomVisitor;
+
+macro_rules! visit_scalar_as_atom {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> Result<Self::Value, E> {
+                Ok(Atom {
+                    value: value.to_string(),
+                })
+            }
+        )*
+    };
+}
+
+impl<'de> Visitor<'de> for AtomVisitor {
+    type Value = Atom;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string")
+    }
+
+    visit_scalar_as_atom! {
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+        Ok(Atom { value })
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Bytes(value), &self)),
+        }
+    }
+}
+
+struct MapVisitor;
+
+impl<'de> Visitor<'de> for MapVisitor {
+    type Value = Map;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map with identifier keys")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = Map {
+            entries: Vec::with_capacity(access.size_hint().unwrap_or(0).min(4096)),
+        };
+        while let Some((key, value)) = access.next_entry::<Identifier, Node>()? {
+            if let Err(EditError::DuplicateKey(key)) = map.try_insert(key, value) {
+                return Err(de::Error::custom(format!("duplicate key {:?}", &*key)));
+            }
+        }
+        Ok(map)
+    }
+}
+
+struct ListVisitor;
+
+impl<'de> Visitor<'de> for ListVisitor {
+    type Value = List;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::with_capacity(access.size_hint().unwrap_or(0).min(4096));
+        while let Some(element) = access.next_element()? {
+            elements.push(element);
+        }
+        Ok(List { elements })
+    }
+}
+
+struct IdentifierVisitor;
+
+impl<'de> Visitor<'de> for IdentifierVisitor {
+    type Value = Identifier;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an identifier")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match Identifier::new(value.to_owned()) {
+            Ok(identifier) => Ok(identifier),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Str(value), &self)),
+        }
+    }
+}
+
+/// Accepts anything an atom, map, or list accepts,
+/// as well as the enum form used by formats that are not human-readable.
+struct NodeVisitor;
+
+macro_rules! forward_to_atom {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> Result<Self::Value, E> {
+                AtomVisitor.$method(value).map(Node::Atom)
+            }
+        )*
+    };
+}
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string, map, or sequence")
+    }
+
+    forward_to_atom! {
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, access: A) -> Result<Self::Value, A::Error> {
+        MapVisitor.visit_map(access).map(Node::Map)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, access: A) -> Result<Self::Value, A::Error> {
+        ListVisitor.visit_seq(access).map(Node::List)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, access: A) -> Result<Self::Value, A::Error> {
+        let (kind, variant) = access.variant::<Kind>()?;
+        match kind {
+            Kind::Atom => variant.newtype_variant().map(Node::Atom),
+            Kind::Map => variant.newtype_variant().map(Node::Map),
+            Kind::List => variant.newtype_variant().map(Node::List),
+        }
+    }
+}
+
+/// The variant of a node written in enum form.
+enum Kind {
+    Atom,
+    Map,
+    List,
+}
+
+impl<'de> Deserialize<'de> for Kind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(KindVisitor)
+    }
+}
+
+struct KindVisitor;
+
+impl<'de> Visitor<'de> for KindVisitor {
+    type Value = Kind;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a node variant")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        match value {
+            0 => Ok(Kind::Atom),
+            1 => Ok(Kind::Map),
+            2 => Ok(Kind::List),
+            _ => Err(de::Error::invalid_value(Unexpected::Unsigned(value), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match value {
+            "Atom" => Ok(Kind::Atom),
+            "Map" => Ok(Kind::Map),
+            "List" => Ok(Kind::List),
+            _ => Err(de::Error::unknown_variant(value, VARIANTS)),
+        }
+    }
+}
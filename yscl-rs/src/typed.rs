@@ -0,0 +1,420 @@
+//! Typed access to atom values.
+//!
+//! YSCL only has string atoms, so numbers, booleans, durations, etc.
+//! must be parsed from atom text.
+//! The [`FromAtom`] trait defines a single set of rules for doing so,
+//! and the `get_*` methods on [`Map`] and [`Node`] apply those rules
+//! to the node at a given [`Path`], reporting the full path
+//! and the offending value when something goes wrong.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//! use std::time::Duration;
+//!
+//! let map = parse_doc(r#"
+//! server = {
+//!     port = "8080"
+//!     verbose = "true"
+//!     timeout = "1m30s"
+//!     hosts = [
+//!         "127.0.0.1"
+//!         "10.0.0.1"
+//!     ]
+//! }
+//! "#).unwrap();
+//!
+//! assert_eq!(map.get_as::<u16>("server.port"), Ok(8080));
+//! assert_eq!(map.get_bool("server.verbose"), Ok(true));
+//! assert_eq!(map.get_duration("server.timeout"), Ok(Duration::from_secs(90)));
+//! assert_eq!(
+//!     map.get_list_of::<std::net::IpAddr>("server.hosts").unwrap().len(),
+//!     2,
+//! );
+//!
+//! let err = map.get_as::<u8>("server.port").unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     r#"server.port: expected u8, found "8080" (number too large to fit in target type)"#,
+//! );
+//! ```
+
+use crate::{path::*, secret::*, tree::*};
+
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// A type that can be parsed from the value of an atom.
+pub trait FromAtom: Sized {
+    /// A short description of the expected format,
+    /// used in error messages (e.g., `"u16"`).
+    const EXPECTED: &'static str;
+
+    /// If `true`, the atom's value is replaced by
+    /// [`REDACTED`] in [`AccessError`]s.
+    const SECRET: bool = false;
+
+    /// On failure, returns a human-readable reason.
+    fn from_atom(value: &str) -> Result<Self, String>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Atom,
+    Map,
+    List,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessError {
+    /// The path string could not be parsed.
+    InvalidPath(PathParseError),
+    /// Nothing exists at the path.
+    Missing(Path),
+    /// The node at the path is not of the expected kind.
+    WrongKind {
+        path: Path,
+        expected: NodeKind,
+        actual: NodeKind,
+    },
+    /// The atom at the path could not be parsed.
+    /// `value` is [`REDACTED`] if the expected type is secret
+    /// (see [`FromAtom::SECRET`]).
+    InvalidValue {
+        path: Path,
+        value: String,
+        expected: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeKind::Atom => write!(f, "atom"),
+            NodeKind::Map => write!(f, "map"),
+            NodeKind::List => write!(f, "list"),
+        }
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessError::InvalidPath(err) => write!(f, "invalid path: {}", err),
+            AccessError::Missing(path) => write!(f, "{}: missing", path),
+            AccessError::WrongKind {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{}: expected {}, found {}", path, expected, actual),
+            AccessError::InvalidValue {
+                path,
+                value,
+                expected,
+                reason,
+            } => write!(
+                f,
+                "{}: expected {}, found {:?} ({})",
+                path, expected, value, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+impl NodeRef<'_> {
+    pub fn kind(self) -> NodeKind {
+        match self {
+            NodeRef::Atom(_) => NodeKind::Atom,
+            NodeRef::Map(_) => NodeKind::Map,
+            NodeRef::List(_) => NodeKind::List,
+        }
+    }
+}
+
+impl Node {
+    pub fn kind(&self) -> NodeKind {
+        self.as_ref().kind()
+    }
+
+    /// Parses the atom at `path` (relative to `self`) as a `T`.
+    pub fn get_as<T: FromAtom>(&self, path: &str) -> Result<T, AccessError> {
+        get_as(self.as_ref(), path)
+    }
+
+    pub fn get_bool(&self, path: &str) -> Result<bool, AccessError> {
+        self.get_as(path)
+    }
+
+    pub fn get_duration(&self, path: &str) -> Result<Duration, AccessError> {
+        self.get_as(path)
+    }
+
+    /// Parses every element of the list at `path` (relative to `self`) as a `T`.
+    pub fn get_list_of<T: FromAtom>(&self, path: &str) -> Result<Vec<T>, AccessError> {
+        get_list_of(self.as_ref(), path)
+    }
+
+    /// Returns the node at `path` (relative to `self`).
+    pub fn get_node(&self, path: &str) -> Result<NodeRef<'_>, AccessError> {
+        get_node(self.as_ref(), path).map(|(_, node)| node)
+    }
+
+    /// Returns the map at `path` (relative to `self`).
+    pub fn get_map(&self, path: &str) -> Result<&Map, AccessError> {
+        get_map(self.as_ref(), path)
+    }
+
+    /// Returns the list at `path` (relative to `self`).
+    pub fn get_list(&self, path: &str) -> Result<&List, AccessError> {
+        get_list(self.as_ref(), path).map(|(_, list)| list)
+    }
+}
+
+impl Map {
+    /// Parses the atom at `path` as a `T`.
+    pub fn get_as<T: FromAtom>(&self, path: &str) -> Result<T, AccessError> {
+        get_as(NodeRef::Map(self), path)
+    }
+
+    pub fn get_bool(&self, path: &str) -> Result<bool, AccessError> {
+        self.get_as(path)
+    }
+
+    pub fn get_duration(&self, path: &str) -> Result<Duration, AccessError> {
+        self.get_as(path)
+    }
+
+    /// Parses every element of the list at `path` as a `T`.
+    pub fn get_list_of<T: FromAtom>(&self, path: &str) -> Result<Vec<T>, AccessError> {
+        get_list_of(NodeRef::Map(self), path)
+    }
+
+    /// Returns the node at `path`.
+    pub fn get_node(&self, path: &str) -> Result<NodeRef<'_>, AccessError> {
+        get_node(NodeRef::Map(self), path).map(|(_, node)| node)
+    }
+
+    /// Returns the map at `path`.
+    pub fn get_map(&self, path: &str) -> Result<&Map, AccessError> {
+        get_map(NodeRef::Map(self), path)
+    }
+
+    /// Returns the list at `path`.
+    pub fn get_list(&self, path: &str) -> Result<&List, AccessError> {
+        get_list(NodeRef::Map(self), path).map(|(_, list)| list)
+    }
+}
+
+fn get_node<'a>(root: NodeRef<'a>, path: &str) -> Result<(Path, NodeRef<'a>), AccessError> {
+    let path: Path = path.parse().map_err(AccessError::InvalidPath)?;
+    match path.resolve(root) {
+        Some(node) => Ok((path, node)),
+        None => Err(AccessError::Missing(path)),
+    }
+}
+
+fn get_map<'a>(root: NodeRef<'a>, path: &str) -> Result<&'a Map, AccessError> {
+    let (path, node) = get_node(root, path)?;
+    node.map().ok_or(AccessError::WrongKind {
+        path,
+        expected: NodeKind::Map,
+        actual: node.kind(),
+    })
+}
+
+fn get_list<'a>(root: NodeRef<'a>, path: &str) -> Result<(Path, &'a List), AccessError> {
+    let (path, node) = get_node(root, path)?;
+    match node.list() {
+        Some(list) => Ok((path, list)),
+        None => Err(AccessError::WrongKind {
+            path,
+            expected: NodeKind::List,
+            actual: node.kind(),
+        }),
+    }
+}
+
+fn get_as<T: FromAtom>(root: NodeRef, path: &str) -> Result<T, AccessError> {
+    let (path, node) = get_node(root, path)?;
+    parse_node(node, &path)
+}
+
+fn get_list_of<T: FromAtom>(root: NodeRef, path: &str) -> Result<Vec<T>, AccessError> {
+    let (path, list) = get_list(root, path)?;
+    list.elements
+        .iter()
+        .enumerate()
+        .map(|(i, element)| parse_node(element.as_ref(), &path.with_index(i)))
+        .collect()
+}
+
+pub(crate) fn parse_node<T: FromAtom>(node: NodeRef, path: &Path) -> Result<T, AccessError> {
+    let NodeRef::Atom(atom) = node else {
+        return Err(AccessError::WrongKind {
+            path: path.clone(),
+            expected: NodeKind::Atom,
+            actual: node.kind(),
+        });
+    };
+    T::from_atom(&atom.value).map_err(|reason| AccessError::InvalidValue {
+        path: path.clone(),
+        value: if T::SECRET {
+            REDACTED.to_owned()
+        } else {
+            atom.value.clone()
+        },
+        expected: T::EXPECTED,
+        reason,
+    })
+}
+
+macro_rules! impl_from_atom_via_from_str {
+    ($($ty:ty),*) => {
+        $(
+            impl FromAtom for $ty {
+                const EXPECTED: &'static str = stringify!($ty);
+
+                fn from_atom(value: &str) -> Result<Self, String> {
+                    value.parse().map_err(|err| format!("{}", err))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_atom_via_from_str!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, IpAddr, Ipv4Addr,
+    Ipv6Addr, SocketAddr
+);
+
+impl FromAtom for String {
+    const EXPECTED: &'static str = "string";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        Ok(value.to_owned())
+    }
+}
+
+impl FromAtom for PathBuf {
+    const EXPECTED: &'static str = "path";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        if value.is_empty() {
+            return Err("path is empty".to_owned());
+        }
+        Ok(PathBuf::from(value))
+    }
+}
+
+/// Paths use the same syntax as [`Path`]'s `FromStr` impl.
+impl FromAtom for Path {
+    const EXPECTED: &'static str = "path";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|err| match err {
+            PathParseError::UnexpectedChar(c, i) => {
+                format!("unexpected {:?} at byte {}", c, i)
+            }
+            PathParseError::UnexpectedEoi => "unexpected end of input".to_owned(),
+        })
+    }
+}
+
+/// Only `true` and `false` are accepted.
+impl FromAtom for bool {
+    const EXPECTED: &'static str = "bool";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        match value {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err("expected `true` or `false`".to_owned()),
+        }
+    }
+}
+
+impl FromAtom for char {
+    const EXPECTED: &'static str = "char";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err("expected exactly one character".to_owned()),
+        }
+    }
+}
+
+/// A duration is one or more `<integer><unit>` pairs,
+/// where the unit is one of `d`, `h`, `m`, `s`, `ms`, `us`, or `ns`
+/// (e.g., `"1h30m"`, `"250ms"`).
+/// The units must appear in decreasing order of size, at most once each.
+impl FromAtom for Duration {
+    const EXPECTED: &'static str = "duration";
+
+    fn from_atom(value: &str) -> Result<Self, String> {
+        const UNITS: [(&str, u64); 7] = [
+            ("d", 86_400_000_000_000),
+            ("h", 3_600_000_000_000),
+            ("m", 60_000_000_000),
+            ("s", 1_000_000_000),
+            ("ms", 1_000_000),
+            ("us", 1_000),
+            ("ns", 1),
+        ];
+
+        if value.is_empty() {
+            return Err("duration is empty".to_owned());
+        }
+
+        let mut total = Duration::ZERO;
+        let mut remaining = value;
+        let mut min_unit_index = 0;
+        while !remaining.is_empty() {
+            let digits_len = remaining
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(remaining.len());
+            if digits_len == 0 {
+                return Err("expected a number before each unit".to_owned());
+            }
+            let amount: u64 = remaining[..digits_len]
+                .parse()
+                .map_err(|err| format!("{}", err))?;
+            remaining = &remaining[digits_len..];
+
+            let unit_len = remaining
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(remaining.len());
+            let unit = &remaining[..unit_len];
+            remaining = &remaining[unit_len..];
+
+            let Some(unit_index) = UNITS.iter().position(|(name, _)| *name == unit) else {
+                return Err(if unit.is_empty() {
+                    "missing unit (expected one of d, h, m, s, ms, us, ns)".to_owned()
+                } else {
+                    format!("unknown unit {:?}", unit)
+                });
+            };
+            if unit_index < min_unit_index {
+                return Err("units must be in decreasing order and not repeated".to_owned());
+            }
+            min_unit_index = unit_index + 1;
+
+            let nanos = amount
+                .checked_mul(UNITS[unit_index].1)
+                .ok_or_else(|| "duration is too large".to_owned())?;
+            total = total
+                .checked_add(Duration::from_nanos(nanos))
+                .ok_or_else(|| "duration is too large".to_owned())?;
+        }
+
+        Ok(total)
+    }
+}
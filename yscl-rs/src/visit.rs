@@ -0,0 +1,228 @@
+//! Traversal of YSCL trees.
+//!
+//! [`Visit`] and [`VisitMut`] follow the usual visitor pattern:
+//! each `visit_*` method defaults to calling the corresponding `walk_*`
+//! function, which recurses into the node's children.
+//! Override only the methods you care about,
+//! and call the `walk_*` function from your override
+//! if you still want to recurse.
+//!
+//! Every method receives the [`Path`] of the node being visited.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! struct UrlCollector(Vec<String>);
+//!
+//! impl<'a> Visit<'a> for UrlCollector {
+//!     fn visit_atom(&mut self, path: &Path, atom: &'a Atom) {
+//!         if let Some(PathSegment::Key(key)) = path.segments.last() {
+//!             if &**key == "url" {
+//!                 self.0.push(format!("{} = {}", path, atom.value));
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let map = parse_doc(r#"
+//! homepage = {
+//!     url = "https://example.com"
+//! }
+//! mirrors = [
+//!     {
+//!         url = "https://mirror.example.com"
+//!     }
+//! ]
+//! "#).unwrap();
+//!
+//! let mut collector = UrlCollector(vec![]);
+//! collector.visit_map(&Path::root(), &map);
+//! assert_eq!(
+//!     collector.0,
+//!     vec![
+//!         "homepage.url = https://example.com",
+//!         "mirrors[0].url = https://mirror.example.com",
+//!     ],
+//! );
+//! ```
+
+use crate::{path::*, tree::*};
+
+pub trait Visit<'a> {
+    fn visit_node(&mut self, path: &Path, node: NodeRef<'a>) {
+        walk_node(self, path, node);
+    }
+
+    fn visit_map(&mut self, path: &Path, map: &'a Map) {
+        walk_map(self, path, map);
+    }
+
+    /// `path` is the path of the entry's value.
+    fn visit_entry(&mut self, path: &Path, entry: &'a MapEntry) {
+        walk_entry(self, path, entry);
+    }
+
+    fn visit_list(&mut self, path: &Path, list: &'a List) {
+        walk_list(self, path, list);
+    }
+
+    fn visit_atom(&mut self, _path: &Path, _atom: &'a Atom) {}
+}
+
+pub fn walk_node<'a, V>(visitor: &mut V, path: &Path, node: NodeRef<'a>)
+where
+    V: ?Sized + Visit<'a>,
+{
+    match node {
+        NodeRef::Atom(atom) => visitor.visit_atom(path, atom),
+        NodeRef::Map(map) => visitor.visit_map(path, map),
+        NodeRef::List(list) => visitor.visit_list(path, list),
+    }
+}
+
+pub fn walk_map<'a, V>(visitor: &mut V, path: &Path, map: &'a Map)
+where
+    V: ?Sized + Visit<'a>,
+{
+    for entry in &map.entries {
+        visitor.visit_entry(&path.with_key(entry.key.clone()), entry);
+    }
+}
+
+pub fn walk_entry<'a, V>(visitor: &mut V, path: &Path, entry: &'a MapEntry)
+where
+    V: ?Sized + Visit<'a>,
+{
+    visitor.visit_node(path, entry.value.as_ref());
+}
+
+pub fn walk_list<'a, V>(visitor: &mut V, path: &Path, list: &'a List)
+where
+    V: ?Sized + Visit<'a>,
+{
+    for (i, element) in list.elements.iter().enumerate() {
+        visitor.visit_node(&path.with_index(i), element.as_ref());
+    }
+}
+
+pub trait VisitMut {
+    fn visit_node_mut(&mut self, path: &Path, node: &mut Node) {
+        walk_node_mut(self, path, node);
+    }
+
+    fn visit_map_mut(&mut self, path: &Path, map: &mut Map) {
+        walk_map_mut(self, path, map);
+    }
+
+    /// `path` is the path of the entry's value.
+    /// The key cannot be changed, since that could make it collide
+    /// with another key of the map.
+    fn visit_entry_mut(&mut self, path: &Path, key: &Identifier, value: &mut Node) {
+        walk_entry_mut(self, path, key, value);
+    }
+
+    fn visit_list_mut(&mut self, path: &Path, list: &mut List) {
+        walk_list_mut(self, path, list);
+    }
+
+    fn visit_atom_mut(&mut self, _path: &Path, _atom: &mut Atom) {}
+}
+
+pub fn walk_node_mut<V>(visitor: &mut V, path: &Path, node: &mut Node)
+where
+    V: ?Sized + VisitMut,
+{
+    match node {
+        Node::Atom(atom) => visitor.visit_atom_mut(path, atom),
+        Node::Map(map) => visitor.visit_map_mut(path, map),
+        Node::List(list) => visitor.visit_list_mut(path, list),
+    }
+}
+
+pub fn walk_map_mut<V>(visitor: &mut V, path: &Path, map: &mut Map)
+where
+    V: ?Sized + VisitMut,
+{
+    for entry in &mut map.entries {
+        let entry_path = path.with_key(entry.key.clone());
+        visitor.visit_entry_mut(&entry_path, &entry.key, &mut entry.value);
+    }
+}
+
+pub fn walk_entry_mut<V>(visitor: &mut V, path: &Path, _key: &Identifier, value: &mut Node)
+where
+    V: ?Sized + VisitMut,
+{
+    visitor.visit_node_mut(path, value);
+}
+
+pub fn walk_list_mut<V>(visitor: &mut V, path: &Path, list: &mut List)
+where
+    V: ?Sized + VisitMut,
+{
+    for (i, element) in list.elements.iter_mut().enumerate() {
+        visitor.visit_node_mut(&path.with_index(i), element);
+    }
+}
+
+/// A pre-order, depth-first iterator over a node and all its descendants.
+///
+/// ## Example
+/// ```rust
+/// use yscl::prelude::*;
+///
+/// let node = yscl_node!({
+///     foo = "bar",
+///     list = ["a", "b"]
+/// });
+/// let paths: Vec<String> = node
+///     .as_ref()
+///     .depth_first()
+///     .map(|(path, _)| path.to_string())
+///     .collect();
+/// assert_eq!(paths, vec!["", "foo", "list", "list[0]", "list[1]"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DepthFirst<'a> {
+    stack: Vec<(Path, NodeRef<'a>)>,
+}
+
+impl<'a> NodeRef<'a> {
+    /// Returns an iterator over `self` and all its descendants,
+    /// with paths relative to `self`.
+    pub fn depth_first(self) -> DepthFirst<'a> {
+        DepthFirst {
+            stack: vec![(Path::root(), self)],
+        }
+    }
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = (Path, NodeRef<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        match node {
+            NodeRef::Atom(_) => {}
+            NodeRef::Map(map) => {
+                self.stack.extend(
+                    map.entries
+                        .iter()
+                        .rev()
+                        .map(|entry| (path.with_key(entry.key.clone()), entry.value.as_ref())),
+                );
+            }
+            NodeRef::List(list) => {
+                self.stack.extend(
+                    list.elements
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(i, element)| (path.with_index(i), element.as_ref())),
+                );
+            }
+        }
+        Some((path, node))
+    }
+}
@@ -0,0 +1,462 @@
+//! Writing YSCL documents.
+//!
+//! The output is always valid strict YSCL, and parsing it returns
+//! the original map: `parse_doc(&to_string(&map)) == Ok(map)`.
+//! To write a document without building a [`Map`] first, use [`YsclWriter`],
+//! which produces the same output.
+//!
+//! - Each entry and list element is written on its own line,
+//!   indented by one [`WriteOptions::indent`] per level of nesting.
+//! - Empty maps and lists are written as `{}` and `[]`.
+//! - Atoms are escaped with [`escape`] using [`WriteOptions::escape_mode`].
+//!   By default, `"`, `\`, and newlines are escaped as `\"`, `\\`, and `\n`,
+//!   non-printable characters are escaped as `\u` followed by 6 hex digits,
+//!   and everything else is written as is.
+//!
+//! ## Example
+//! ```rust
+//! use yscl::prelude::*;
+//!
+//! let map = yscl_node!({
+//!     name = "say \"hi\"",
+//!     tags = [],
+//!     deps = { yscl = "1.0.0" }
+//! })
+//! .map()
+//! .unwrap();
+//!
+//! let src = to_string(&map);
+//! assert_eq!(
+//!     src,
+//!     r#"name = "say \"hi\""
+//! tags = []
+//! deps = {
+//!     yscl = "1.0.0"
+//! }
+//! "#,
+//! );
+//! assert_eq!(parse_doc(&src), Ok(map));
+//! ```
+
+use crate::{escape::*, tree::*};
+
+use std::{collections::HashSet, fmt, io};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WriteOptions {
+    /// The text written once per level of nesting.
+    /// This should only contain spaces and tabs,
+    /// or the output will not be valid YSCL.
+    pub indent: String,
+    pub escape_mode: EscapeMode,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_owned(),
+            escape_mode: EscapeMode::default(),
+        }
+    }
+}
+
+impl WriteOptions {
+    pub fn with_indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    pub fn with_escape_mode(mut self, escape_mode: EscapeMode) -> Self {
+        self.escape_mode = escape_mode;
+        self
+    }
+}
+
+/// Writes `map` as a YSCL document, using the default options.
+pub fn to_string(map: &Map) -> String {
+    to_string_with(map, &WriteOptions::default())
+}
+
+pub fn to_string_with(map: &Map, options: &WriteOptions) -> String {
+    let mut out = vec![];
+    write_doc_with(&mut out, map, options).expect("Writing to a Vec should succeed");
+    String::from_utf8(out).expect("Output should be valid UTF-8")
+}
+
+/// Writes `map` as a YSCL document to `out`, using the default options.
+pub fn write_doc<W: io::Write + ?Sized>(out: &mut W, map: &Map) -> io::Result<()> {
+    write_doc_with(out, map, &WriteOptions::default())
+}
+
+pub fn write_doc_with<W: io::Write + ?Sized>(
+    out: &mut W,
+    map: &Map,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    write_entries(out, map, options, 0)
+}
+
+fn write_entries<W: io::Write + ?Sized>(
+    out: &mut W,
+    map: &Map,
+    options: &WriteOptions,
+    depth: usize,
+) -> io::Result<()> {
+    for entry in &map.entries {
+        write_indent(out, options, depth)?;
+        write!(out, "{} = ", &*entry.key)?;
+        write_node(out, &entry.value, options, depth)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_node<W: io::Write + ?Sized>(
+    out: &mut W,
+    node: &Node,
+    options: &WriteOptions,
+    depth: usize,
+) -> io::Result<()> {
+    match node {
+        Node::Atom(atom) => write!(out, "\"{}\"", escape(&atom.value, options.escape_mode)),
+        Node::Map(map) if map.entries.is_empty() => write!(out, "{{}}"),
+        Node::Map(map) => {
+            writeln!(out, "{{")?;
+            write_entries(out, map, options, depth + 1)?;
+            write_indent(out, options, depth)?;
+            write!(out, "}}")
+        }
+        Node::List(list) if list.elements.is_empty() => write!(out, "[]"),
+        Node::List(list) => {
+            writeln!(out, "[")?;
+            for element in &list.elements {
+                write_indent(out, options, depth + 1)?;
+                write_node(out, element, options, depth + 1)?;
+                writeln!(out)?;
+            }
+            write_indent(out, options, depth)?;
+            write!(out, "]")
+        }
+    }
+}
+
+fn write_indent<W: io::Write + ?Sized>(
+    out: &mut W,
+    options: &WriteOptions,
+    depth: usize,
+) -> io::Result<()> {
+    for _ in 0..depth {
+        out.write_all(options.indent.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Writes a YSCL document incrementally, without building a [`Map`] first.
+///
+/// The document itself is the top-level map, so start with [`YsclWriter::key`].
+/// Every value (an atom, map, or list) in a map must be preceded by a key.
+/// Calls that would produce an invalid document (invalid or duplicate keys,
+/// values without keys, mismatched ends, etc.) return an error and write
+/// nothing, so the writer can still be used afterward.
+///
+/// Call [`YsclWriter::finish`] when done, to check that every map and list
+/// was ended.
+///
+/// ## Example
+/// ```rust
+/// use yscl::prelude::*;
+///
+/// let mut writer = YsclWriter::new(vec![]);
+/// writer.comment("Generated.").unwrap();
+/// writer.key("name").unwrap();
+/// writer.atom("yscl").unwrap();
+/// writer.key("rows").unwrap();
+/// writer.begin_list().unwrap();
+/// for i in 0..2 {
+///     writer.begin_map().unwrap();
+///     writer.key("id").unwrap();
+///     writer.atom(&i.to_string()).unwrap();
+///     writer.end_map().unwrap();
+/// }
+/// writer.end_list().unwrap();
+/// writer.key("tags").unwrap();
+/// writer.begin_list().unwrap();
+/// writer.end_list().unwrap();
+///
+/// assert_eq!(writer.key("name"), Err(YsclWriterError::DuplicateKey("name".to_owned())));
+///
+/// let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert_eq!(
+///     out,
+///     r#"// Generated.
+/// name = "yscl"
+/// rows = [
+///     {
+///         id = "0"
+///     }
+///     {
+///         id = "1"
+///     }
+/// ]
+/// tags = []
+/// "#,
+/// );
+/// assert!(parse_doc(&out).is_ok());
+/// ```
+#[derive(Debug)]
+pub struct YsclWriter<W> {
+    out: W,
+    options: WriteOptions,
+    /// The maps and lists that have been started but not ended.
+    /// The first frame is the document itself.
+    stack: Vec<Frame>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    kind: FrameKind,
+    /// Whether anything has been written inside this map or list yet.
+    /// The first item is preceded by a newline, so that maps and lists
+    /// with no items can be written as `{}` and `[]`.
+    has_items: bool,
+}
+
+#[derive(Debug)]
+enum FrameKind {
+    Map {
+        keys: HashSet<String>,
+        pending_key: Option<String>,
+    },
+    List,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum YsclWriterError {
+    Io(io::ErrorKind),
+    /// The key is empty or contains a character that is not allowed.
+    InvalidKey(String),
+    /// The key was already written in the current map.
+    DuplicateKey(String),
+    /// A value was written in a map without a key.
+    MissingKey,
+    /// The key was written, but its value was not.
+    MissingValue(String),
+    /// A key was written in a list.
+    UnexpectedKey(String),
+    /// `end_map` or `end_list` did not match the innermost open map or list
+    /// (or there was none).
+    UnexpectedEnd,
+    /// The comment contains a newline.
+    InvalidComment(String),
+    /// `finish` was called before every map and list was ended.
+    Unfinished,
+}
+
+impl fmt::Display for YsclWriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YsclWriterError::Io(kind) => write!(f, "{}", io::Error::from(*kind)),
+            YsclWriterError::InvalidKey(key) => write!(f, "invalid key {:?}", key),
+            YsclWriterError::DuplicateKey(key) => write!(f, "duplicate key {:?}", key),
+            YsclWriterError::MissingKey => write!(f, "value in a map has no key"),
+            YsclWriterError::MissingValue(key) => write!(f, "key {:?} has no value", key),
+            YsclWriterError::UnexpectedKey(key) => write!(f, "key {:?} is inside a list", key),
+            YsclWriterError::UnexpectedEnd => write!(f, "end does not match the open map or list"),
+            YsclWriterError::InvalidComment(comment) => {
+                write!(f, "comment {:?} contains a newline", comment)
+            }
+            YsclWriterError::Unfinished => write!(f, "not every map and list was ended"),
+        }
+    }
+}
+
+impl std::error::Error for YsclWriterError {}
+
+impl From<io::Error> for YsclWriterError {
+    fn from(err: io::Error) -> Self {
+        YsclWriterError::Io(err.kind())
+    }
+}
+
+impl<W: io::Write> YsclWriter<W> {
+    /// Creates a writer that uses the default options.
+    pub fn new(out: W) -> Self {
+        Self::with_options(out, WriteOptions::default())
+    }
+
+    pub fn with_options(out: W, options: WriteOptions) -> Self {
+        Self {
+            out,
+            options,
+            stack: vec![Frame {
+                kind: FrameKind::Map {
+                    keys: HashSet::new(),
+                    pending_key: None,
+                },
+                // The document has no opening brace to put a newline after.
+                has_items: true,
+            }],
+        }
+    }
+
+    /// Writes the key of the next entry in the current map.
+    pub fn key(&mut self, key: &str) -> Result<(), YsclWriterError> {
+        match &self.top().kind {
+            FrameKind::List => return Err(YsclWriterError::UnexpectedKey(key.to_owned())),
+            FrameKind::Map {
+                pending_key: Some(pending_key),
+                ..
+            } => return Err(YsclWriterError::MissingValue(pending_key.clone())),
+            FrameKind::Map { keys, .. } => {
+                if Identifier::new(key.to_owned()).is_err() {
+                    return Err(YsclWriterError::InvalidKey(key.to_owned()));
+                }
+                if keys.contains(key) {
+                    return Err(YsclWriterError::DuplicateKey(key.to_owned()));
+                }
+            }
+        }
+
+        self.start_line()?;
+        write!(self.out, "{} = ", key)?;
+        if let FrameKind::Map { keys, pending_key } = &mut self.top_mut().kind {
+            keys.insert(key.to_owned());
+            *pending_key = Some(key.to_owned());
+        }
+        Ok(())
+    }
+
+    /// Writes an atom, escaped with the writer's [`WriteOptions::escape_mode`].
+    pub fn atom(&mut self, value: &str) -> Result<(), YsclWriterError> {
+        self.start_value()?;
+        writeln!(self.out, "\"{}\"", escape(value, self.options.escape_mode))?;
+        Ok(())
+    }
+
+    pub fn begin_map(&mut self) -> Result<(), YsclWriterError> {
+        self.start_value()?;
+        write!(self.out, "{{")?;
+        self.stack.push(Frame {
+            kind: FrameKind::Map {
+                keys: HashSet::new(),
+                pending_key: None,
+            },
+            has_items: false,
+        });
+        Ok(())
+    }
+
+    pub fn end_map(&mut self) -> Result<(), YsclWriterError> {
+        match &self.top().kind {
+            FrameKind::Map {
+                pending_key: Some(pending_key),
+                ..
+            } => return Err(YsclWriterError::MissingValue(pending_key.clone())),
+            FrameKind::Map { .. } if self.stack.len() > 1 => {}
+            _ => return Err(YsclWriterError::UnexpectedEnd),
+        }
+        self.end('}')
+    }
+
+    pub fn begin_list(&mut self) -> Result<(), YsclWriterError> {
+        self.start_value()?;
+        write!(self.out, "[")?;
+        self.stack.push(Frame {
+            kind: FrameKind::List,
+            has_items: false,
+        });
+        Ok(())
+    }
+
+    pub fn end_list(&mut self) -> Result<(), YsclWriterError> {
+        match &self.top().kind {
+            FrameKind::List => self.end(']'),
+            FrameKind::Map { .. } => Err(YsclWriterError::UnexpectedEnd),
+        }
+    }
+
+    /// Writes a `//` comment on its own line.
+    /// Comments can go anywhere a key (in a map) or a value (in a list) can.
+    pub fn comment(&mut self, comment: &str) -> Result<(), YsclWriterError> {
+        if comment.contains('\n') {
+            return Err(YsclWriterError::InvalidComment(comment.to_owned()));
+        }
+        if let FrameKind::Map {
+            pending_key: Some(pending_key),
+            ..
+        } = &self.top().kind
+        {
+            return Err(YsclWriterError::MissingValue(pending_key.clone()));
+        }
+
+        self.start_line()?;
+        if comment.is_empty() {
+            writeln!(self.out, "//")?;
+        } else {
+            writeln!(self.out, "// {}", comment)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the document is complete, flushes the output,
+    /// and returns it.
+    pub fn finish(mut self) -> Result<W, YsclWriterError> {
+        if self.stack.len() > 1 {
+            return Err(YsclWriterError::Unfinished);
+        }
+        if let FrameKind::Map {
+            pending_key: Some(pending_key),
+            ..
+        } = &self.top().kind
+        {
+            return Err(YsclWriterError::MissingValue(pending_key.clone()));
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn top(&self) -> &Frame {
+        self.stack.last().expect("Stack should never be empty")
+    }
+
+    fn top_mut(&mut self) -> &mut Frame {
+        self.stack.last_mut().expect("Stack should never be empty")
+    }
+
+    /// Checks that a value can be written here, and writes
+    /// everything that goes before it.
+    fn start_value(&mut self) -> Result<(), YsclWriterError> {
+        match &mut self.top_mut().kind {
+            FrameKind::Map { pending_key, .. } => {
+                if pending_key.take().is_none() {
+                    return Err(YsclWriterError::MissingKey);
+                }
+                // The key (and its indentation) is already written.
+                Ok(())
+            }
+            FrameKind::List => self.start_line(),
+        }
+    }
+
+    /// Writes the newline after the opening brace (for the first item)
+    /// and the indentation of a new line.
+    fn start_line(&mut self) -> Result<(), YsclWriterError> {
+        let depth = self.stack.len() - 1;
+        let top = self.top_mut();
+        if !top.has_items {
+            top.has_items = true;
+            writeln!(self.out)?;
+        }
+        write_indent(&mut self.out, &self.options, depth)?;
+        Ok(())
+    }
+
+    fn end(&mut self, closer: char) -> Result<(), YsclWriterError> {
+        let frame = self.stack.pop().expect("Stack should never be empty");
+        if frame.has_items {
+            write_indent(&mut self.out, &self.options, self.stack.len() - 1)?;
+        }
+        writeln!(self.out, "{}", closer)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//! `#[derive(FromYscl)]` for the [yscl](https://docs.rs/yscl) crate.
+//!
+//! Use it through yscl's `derive` feature, which re-exports it as
+//! `yscl::extract::FromYscl`.
+//! See the `yscl::extract` module for the supported attributes.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Field,
+    Fields, Generics, LitStr,
+};
+
+#[proc_macro_derive(FromYscl, attributes(yscl))]
+pub fn derive_from_yscl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => struct_body(&data.fields, quote!(Self), data.fields.span())?,
+        Data::Enum(data) => {
+            let mut unit_arms = vec![];
+            let mut entry_arms = vec![];
+            let mut names = vec![];
+            for variant in &data.variants {
+                let attrs = Attrs::parse(&variant.attrs)?;
+                if attrs.default.is_some() || attrs.from_str.is_some() || attrs.secret {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "only `rename` is supported on variants",
+                    ));
+                }
+                let key = key(attrs.rename, &variant.ident)?;
+                let ident = &variant.ident;
+                match &variant.fields {
+                    Fields::Unit => unit_arms.push(quote! {
+                        #key => ::std::result::Result::Ok(Self::#ident),
+                    }),
+                    fields => {
+                        let build = struct_body(fields, quote!(Self::#ident), variant.span())?;
+                        entry_arms.push(quote! {
+                            #key => {
+                                let node = entry.value.as_ref();
+                                let path = &path.with_key(entry.key.clone());
+                                #build
+                            }
+                        });
+                    }
+                }
+                names.push(format!("`{}`", key.value()));
+            }
+            let expected = LitStr::new(&format!("one of {}", names.join(", ")), Span::call_site());
+            quote! {
+                match node {
+                    ::yscl::tree::NodeRef::Atom(atom) => match atom.value.as_str() {
+                        #(#unit_arms)*
+                        other => ::yscl::extract::unknown_variant(other, path, #expected),
+                    },
+                    _ => {
+                        let entry = ::yscl::extract::variant_entry(node, path, #expected)?;
+                        match &*entry.key {
+                            #(#entry_arms)*
+                            other => ::yscl::extract::unknown_variant(other, path, #expected),
+                        }
+                    }
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "FromYscl cannot be derived for unions",
+            ))
+        }
+    };
+
+    let generics = add_bounds(input.generics.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::yscl::extract::FromYscl for #name #ty_generics #where_clause {
+            fn from_yscl(
+                node: ::yscl::tree::NodeRef,
+                path: &::yscl::path::Path,
+            ) -> ::std::result::Result<Self, ::yscl::typed::AccessError> {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns an expression that builds `constructor` (a struct or variant)
+/// from `node`, which is located at `path`.
+fn struct_body(fields: &Fields, constructor: TokenStream, span: Span) -> syn::Result<TokenStream> {
+    match fields {
+        Fields::Named(fields) => {
+            let fields = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let attrs = Attrs::parse(&field.attrs)?;
+                    let ident = field
+                        .ident
+                        .as_ref()
+                        .expect("Named fields should have names");
+                    let key = key(attrs.rename.clone(), ident)?;
+                    let parse = parse_fn(field, &attrs)?;
+                    let missing = missing_fn(field, &attrs);
+                    Ok(quote! {
+                        #ident: ::yscl::extract::field(map, path, #key, #parse, #missing)?,
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(quote! {
+                let map = ::yscl::extract::expect_map(node, path)?;
+                ::std::result::Result::Ok(#constructor { #(#fields)* })
+            })
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let field = &fields.unnamed[0];
+            let attrs = Attrs::parse(&field.attrs)?;
+            if attrs.rename.is_some() || attrs.default.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "only `from_str` and `secret` are supported on unnamed fields",
+                ));
+            }
+            let parse = parse_fn(field, &attrs)?;
+            Ok(quote! {
+                ::std::result::Result::Ok(#constructor((#parse)(node, path)?))
+            })
+        }
+        _ => Err(syn::Error::new(
+            span,
+            "FromYscl can only be derived for named fields or a single unnamed field",
+        )),
+    }
+}
+
+fn parse_fn(field: &Field, attrs: &Attrs) -> syn::Result<TokenStream> {
+    let ty = &field.ty;
+    if let Some(expected) = &attrs.from_str {
+        let expected = expected
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| "a value parseable from text".to_owned());
+        let secret = attrs.secret;
+        Ok(quote! {
+            |node, path| ::yscl::extract::parse_from_str::<#ty>(node, path, #expected, #secret)
+        })
+    } else if attrs.secret {
+        Err(syn::Error::new_spanned(
+            field,
+            "`secret` requires `from_str`; use `Secret<T>` for other fields",
+        ))
+    } else {
+        Ok(quote!(<#ty as ::yscl::extract::FromYscl>::from_yscl))
+    }
+}
+
+fn missing_fn(field: &Field, attrs: &Attrs) -> TokenStream {
+    let ty = &field.ty;
+    match &attrs.default {
+        Some(Some(path)) => quote!(|_| ::std::result::Result::Ok(#path())),
+        Some(None) => {
+            quote!(|_| ::std::result::Result::Ok(<#ty as ::std::default::Default>::default()))
+        }
+        None if attrs.from_str.is_some() => quote!(::yscl::extract::missing),
+        None => quote!(<#ty as ::yscl::extract::FromYscl>::from_missing),
+    }
+}
+
+fn key(rename: Option<LitStr>, ident: &syn::Ident) -> syn::Result<LitStr> {
+    let key = rename.unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+    let value = key.value();
+    let is_valid = value
+        .chars()
+        .next()
+        .is_some_and(|first| !first.is_ascii_digit())
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_valid {
+        Ok(key)
+    } else {
+        Err(syn::Error::new(
+            key.span(),
+            format!("{:?} is not a valid YSCL identifier", value),
+        ))
+    }
+}
+
+fn add_bounds(mut generics: Generics) -> Generics {
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::yscl::extract::FromYscl));
+    }
+    generics
+}
+
+#[derive(Default)]
+struct Attrs {
+    rename: Option<LitStr>,
+    /// `Some(None)` for `#[yscl(default)]`,
+    /// and `Some(Some(path))` for `#[yscl(default = "path")]`.
+    default: Option<Option<syn::ExprPath>>,
+    /// `Some(None)` for `#[yscl(from_str)]`,
+    /// and `Some(Some(expected))` for `#[yscl(from_str = "expected")]`.
+    from_str: Option<Option<LitStr>>,
+    /// `true` for `#[yscl(secret)]`.
+    secret: bool,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Attrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("yscl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    parsed.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    parsed.default = if meta.input.peek(syn::Token![=]) {
+                        let path: LitStr = meta.value()?.parse()?;
+                        Some(Some(path.parse()?))
+                    } else {
+                        Some(None)
+                    };
+                } else if meta.path.is_ident("from_str") {
+                    parsed.from_str = if meta.input.peek(syn::Token![=]) {
+                        Some(Some(meta.value()?.parse()?))
+                    } else {
+                        Some(None)
+                    };
+                } else if meta.path.is_ident("secret") {
+                    parsed.secret = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `default`, `from_str`, or `secret`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}